@@ -1,17 +1,90 @@
 // Simplified access control NFT contract demonstrating role-based access control
 // Uses basic Soroban SDK features without OpenZeppelin dependencies
-use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol, Bytes};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Env, Address, IntoVal, Symbol, Bytes, Val, Vec};
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
-    Admin,
     Roles(Address, Symbol), // (address, role)
-    TokenOwner(u64),        // token_id -> owner
-    TokenMetadata(u64),     // token_id -> metadata
+    TokenOwner(u64),        // token_id -> owner (persistent)
+    TokenMetadata(u64),     // token_id -> metadata (persistent)
+    MetadataVersion(u64),   // token_id -> metadata version (persistent)
+    MetadataFrozen(u64),    // token_id -> frozen flag (persistent)
+    OwnerTokens(Address),   // owner -> token ids currently held (persistent)
     NextTokenId,
     TokenName,
     TokenSymbol,
+    TokenCount,                         // number of live tokens
+    HolderCheckpoints(Address),         // owner -> [(distribution_count, token_count)] (persistent)
+    DistributionCount,
+    Distribution(u32),                  // distribution_id -> Distribution (persistent)
+    DistributionClaimed(u32, Address),  // (distribution_id, account) -> claimed flag (persistent)
+    AdminSigners,
+    AdminThreshold,
+    AdminProposalCount,
+    AdminProposal(u32),                 // proposal_id -> pending AdminProposal (persistent)
+    GrantDelay(Symbol),                 // role -> announce-to-activate delay in seconds
+    PendingGrantCount,
+    PendingGrant(u32),                  // grant_id -> PendingGrant awaiting activation (persistent)
+    PublicSale,
+    PublicMinted(Address),              // buyer -> tokens bought in the public sale (persistent)
+    RoleAdmin(Symbol),                  // role -> role whose holders may grant and revoke it
+}
+
+/// A role grant announced ahead of time that can be activated once `eta` passes,
+/// as long as `announcer` still administers the role
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingGrant {
+    pub account: Address,
+    pub role: Symbol,
+    pub announcer: Address,
+    pub eta: u64,
+}
+
+/// Open mint window where anyone can buy tokens for `mint_price` each in `currency`,
+/// paid to `payee`
+#[contracttype]
+#[derive(Clone)]
+pub struct PublicSale {
+    pub currency: Address,
+    pub payee: Address,
+    pub mint_price: i128,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub max_per_tx: u32,
+    pub max_per_wallet: u32,
+    pub max_supply: u64,
+}
+
+/// An admin action that requires M-of-N signer approval
+#[contracttype]
+#[derive(Clone)]
+pub enum AdminAction {
+    GrantAdmin(Address),
+    RevokeAdmin(Address),
+    SetSigners(Vec<Address>, u32),
+    SetPublicSale(PublicSale),
+    SetRoleAdmin(Symbol, Symbol),
+    SetGrantDelay(Symbol, u64),
+}
+
+/// A multi-sig admin action awaiting approvals; removed once it executes
+#[contracttype]
+#[derive(Clone)]
+pub struct AdminProposal {
+    pub action: AdminAction,
+    pub approvals: Vec<Address>,
+}
+
+/// A payout split equally across every NFT that existed when it was created
+#[contracttype]
+#[derive(Clone)]
+pub struct Distribution {
+    pub token: Address,
+    pub amount: i128,
+    pub per_token: i128,
+    pub token_count: u32,
 }
 
 #[contracttype]
@@ -20,21 +93,52 @@ pub enum AccessControlEvent {
     RoleGranted(Address, Symbol),
     RoleRevoked(Address, Symbol),
     NFTMinted(Address, u64),
+    NFTTransferred(Address, Address, u64),
     NFTBurned(u64),
+    MetadataUpdated(u64, u32),
+    MetadataFrozen(u64),
+    DistributionCreated(u32, Address, i128),
+    DistributionClaimed(u32, Address, i128),
+    AdminActionProposed(u32, Address),
+    AdminActionApproved(u32, Address),
+    AdminActionExecuted(u32),
+    AdminSignersChanged(Vec<Address>, u32),
+    GrantAnnounced(u32, Address, Symbol, u64),
+    GrantActivated(u32),
+    GrantCancelled(u32),
+    PublicMinted(Address, u32, i128),
 }
 
+/// Roughly one day of ledgers at a 5 second close time
+const DAY_IN_LEDGERS: u32 = 17280;
+
+/// Per-token entries live 30 days past their last write
+const TOKEN_TTL_EXTEND: u32 = 30 * DAY_IN_LEDGERS;
+const TOKEN_TTL_THRESHOLD: u32 = TOKEN_TTL_EXTEND - DAY_IN_LEDGERS;
+
 #[contract]
 pub struct AccessControlNFT;
 
 #[contractimpl]
 impl AccessControlNFT {
-    /// Initialize the NFT contract with role-based access control
-    pub fn initialize(env: Env, admin: Address, name: Symbol, symbol: Symbol) {
-        if env.storage().instance().has(&DataKey::Admin) {
+    /// Initialize the NFT contract with role-based access control.
+    /// Changes to the admin role, signer set, and collection configuration require
+    /// `threshold` approvals from `signers`.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        name: Symbol,
+        symbol: Symbol,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) {
+        if env.storage().instance().has(&DataKey::AdminSigners) {
             panic!("Already initialized");
         }
         
-        env.storage().instance().set(&DataKey::Admin, &admin);
+        Self::require_valid_signers(&signers, threshold);
+        env.storage().instance().set(&DataKey::AdminSigners, &signers);
+        env.storage().instance().set(&DataKey::AdminThreshold, &threshold);
         env.storage().instance().set(&DataKey::TokenName, &name);
         env.storage().instance().set(&DataKey::TokenSymbol, &symbol);
         env.storage().instance().set(&DataKey::NextTokenId, &1u64);
@@ -51,76 +155,388 @@ impl AccessControlNFT {
         env.storage().instance().get(&DataKey::Roles(account, role)).unwrap_or(false)
     }
     
-    /// Grant a role to an address (admin only)
-    pub fn grant_role(env: Env, account: Address, role: Symbol) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+    /// Get the role whose holders may grant and revoke `role` ("admin" unless set)
+    pub fn role_admin(env: Env, role: Symbol) -> Symbol {
+        env.storage().instance()
+            .get(&DataKey::RoleAdmin(role))
+            .unwrap_or_else(|| Symbol::new(&env, "admin"))
+    }
+    
+    /// Grant a role to an address (role admin only, admin role goes through multi-sig).
+    /// Roles with a grant delay must go through `announce_grant` instead.
+    pub fn grant_role(env: Env, caller: Address, account: Address, role: Symbol) {
+        caller.require_auth();
+        Self::require_not_admin_role(&env, &role);
+        Self::require_role_admin(&env, &caller, &role);
+        if Self::grant_delay(env.clone(), role.clone()) > 0 {
+            panic!("Role grant requires announcement");
+        }
         
         env.storage().instance().set(&DataKey::Roles(account.clone(), role.clone()), &true);
         env.events().publish((Symbol::new(&env, "role_granted"),), (account, role));
     }
     
-    /// Revoke a role from an address (admin only)
-    pub fn revoke_role(env: Env, account: Address, role: Symbol) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+    /// Revoke a role from an address (role admin only, admin role goes through multi-sig)
+    pub fn revoke_role(env: Env, caller: Address, account: Address, role: Symbol) {
+        caller.require_auth();
+        Self::require_not_admin_role(&env, &role);
+        Self::require_role_admin(&env, &caller, &role);
         
         env.storage().instance().set(&DataKey::Roles(account.clone(), role.clone()), &false);
         env.events().publish((Symbol::new(&env, "role_revoked"),), (account, role));
     }
     
-    /// Mint NFT (requires minter role)
-    pub fn mint(env: Env, to: Address, metadata: Bytes) -> u64 {
-        let minter_role = Symbol::new(&env, "minter");
+    /// Get the grant announcement delay in seconds for `role` (zero if it can be granted immediately).
+    /// A non-zero delay, set through `AdminAction::SetGrantDelay`, makes `role` timelocked.
+    pub fn grant_delay(env: Env, role: Symbol) -> u64 {
+        env.storage().instance().get(&DataKey::GrantDelay(role)).unwrap_or(0)
+    }
+    
+    /// Announce a role grant that can be activated after the role's grant delay (role admin only)
+    pub fn announce_grant(env: Env, caller: Address, account: Address, role: Symbol) -> u32 {
+        caller.require_auth();
+        Self::require_not_admin_role(&env, &role);
+        Self::require_role_admin(&env, &caller, &role);
+        
+        let grant_id: u32 = env.storage().instance().get(&DataKey::PendingGrantCount).unwrap_or(0) + 1;
+        let eta = env.ledger().timestamp() + Self::grant_delay(env.clone(), role.clone());
+        let grant = PendingGrant {
+            account: account.clone(),
+            role: role.clone(),
+            announcer: caller,
+            eta,
+        };
+        Self::set_persistent(&env, &DataKey::PendingGrant(grant_id), &grant);
+        env.storage().instance().set(&DataKey::PendingGrantCount, &grant_id);
+        
+        env.events().publish((Symbol::new(&env, "grant_announced"),), (grant_id, account, role, eta));
+        grant_id
+    }
+    
+    /// Apply an announced role grant once its ETA has passed, provided the announcer
+    /// still administers the role. The pending grant is removed once applied.
+    pub fn activate_grant(env: Env, grant_id: u32) {
+        let grant = Self::pending_grant(&env, grant_id);
+        
+        if env.ledger().timestamp() < grant.eta {
+            panic!("Grant is not yet active");
+        }
+        Self::require_role_admin(&env, &grant.announcer, &grant.role);
+        
+        env.storage().persistent().remove(&DataKey::PendingGrant(grant_id));
+        env.storage().instance().set(&DataKey::Roles(grant.account.clone(), grant.role.clone()), &true);
+        
+        env.events().publish((Symbol::new(&env, "role_granted"),), (grant.account, grant.role));
+        env.events().publish((Symbol::new(&env, "grant_activated"),), grant_id);
+    }
+    
+    /// Withdraw an announced role grant before it is activated (announcer or role admin)
+    pub fn cancel_grant(env: Env, caller: Address, grant_id: u32) {
+        caller.require_auth();
+        let grant = Self::pending_grant(&env, grant_id);
+        if caller != grant.announcer {
+            Self::require_role_admin(&env, &caller, &grant.role);
+        }
         
-        // Check if caller has minter role or is admin
-        let caller = env.current_contract_address(); // In real implementation, this would be msg.sender
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage().persistent().remove(&DataKey::PendingGrant(grant_id));
+        env.events().publish((Symbol::new(&env, "grant_cancelled"),), grant_id);
+    }
+    
+    /// Get an announced role grant that has not been activated or cancelled
+    pub fn get_pending_grant(env: Env, grant_id: u32) -> Option<PendingGrant> {
+        env.storage().persistent().get(&DataKey::PendingGrant(grant_id))
+    }
+    
+    /// Propose an admin action (signer only); the proposer's approval is counted
+    pub fn propose_admin_action(env: Env, signer: Address, action: AdminAction) -> u32 {
+        signer.require_auth();
+        Self::require_signer(&env, &signer);
+        match &action {
+            AdminAction::SetSigners(signers, threshold) => Self::require_valid_signers(signers, *threshold),
+            AdminAction::SetPublicSale(sale) => Self::require_valid_sale(sale),
+            AdminAction::SetRoleAdmin(role, _) | AdminAction::SetGrantDelay(role, _) => {
+                Self::require_not_admin_role(&env, role)
+            }
+            AdminAction::GrantAdmin(_) | AdminAction::RevokeAdmin(_) => {}
+        }
+        
+        let proposal_id: u32 = env.storage().instance().get(&DataKey::AdminProposalCount).unwrap_or(0) + 1;
+        let proposal = AdminProposal {
+            action,
+            approvals: Vec::new(&env),
+        };
+        Self::set_persistent(&env, &DataKey::AdminProposal(proposal_id), &proposal);
+        env.storage().instance().set(&DataKey::AdminProposalCount, &proposal_id);
+        
+        env.events().publish((Symbol::new(&env, "admin_action_proposed"),), (proposal_id, signer.clone()));
+        Self::record_approval(&env, proposal_id, signer);
+        proposal_id
+    }
+    
+    /// Approve a pending admin action (signer only); executes once the threshold is met
+    pub fn approve_admin_action(env: Env, signer: Address, proposal_id: u32) {
+        signer.require_auth();
+        Self::require_signer(&env, &signer);
+        
+        Self::record_approval(&env, proposal_id, signer);
+    }
+    
+    /// Get a pending multi-sig admin proposal (`None` once executed)
+    pub fn get_admin_proposal(env: Env, proposal_id: u32) -> Option<AdminProposal> {
+        env.storage().persistent().get(&DataKey::AdminProposal(proposal_id))
+    }
+    
+    /// Get the admin signer set
+    pub fn admin_signers(env: Env) -> Vec<Address> {
+        env.storage().instance().get(&DataKey::AdminSigners).unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get the number of signer approvals required for admin actions
+    pub fn admin_threshold(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::AdminThreshold).unwrap_or(1)
+    }
+    
+    /// Mint NFT (requires minter role or admin). Admin-role holders mint directly like
+    /// minters: the role itself is only granted through a multi-sig proposal.
+    pub fn mint(env: Env, minter: Address, to: Address, metadata: Bytes) -> u64 {
+        minter.require_auth();
+        let minter_role = Symbol::new(&env, "minter");
         
-        if !Self::has_role(env.clone(), caller.clone(), minter_role.clone()) && caller != admin {
+        if !Self::has_role(env.clone(), minter.clone(), minter_role) && !Self::is_admin(&env, &minter) {
             panic!("Caller does not have minter role");
         }
         
-        let token_id: u64 = env.storage().instance().get(&DataKey::NextTokenId).unwrap_or(1);
+        Self::mint_token(&env, &to, &metadata)
+    }
+    
+    /// Get the public sale configuration, set through `AdminAction::SetPublicSale`
+    pub fn public_sale(env: Env) -> Option<PublicSale> {
+        env.storage().instance().get(&DataKey::PublicSale)
+    }
+    
+    /// Get the number of tokens a buyer has bought in the public sale
+    pub fn public_minted(env: Env, buyer: Address) -> u32 {
+        env.storage().persistent().get(&DataKey::PublicMinted(buyer)).unwrap_or(0)
+    }
+    
+    /// Buy `quantity` tokens during the public sale, paying the payee `mint_price * quantity`
+    pub fn public_mint(env: Env, buyer: Address, quantity: u32) -> Vec<u64> {
+        buyer.require_auth();
         
-        env.storage().instance().set(&DataKey::TokenOwner(token_id), &to);
-        env.storage().instance().set(&DataKey::TokenMetadata(token_id), &metadata);
-        env.storage().instance().set(&DataKey::NextTokenId, &(token_id + 1));
+        let sale: PublicSale = env.storage().instance()
+            .get(&DataKey::PublicSale)
+            .unwrap_or_else(|| panic!("Public sale not configured"));
+        let now = env.ledger().timestamp();
+        if now < sale.start_time || now >= sale.end_time {
+            panic!("Public sale is not active");
+        }
+        if quantity == 0 || quantity > sale.max_per_tx {
+            panic!("Quantity exceeds per-transaction cap");
+        }
         
-        env.events().publish((Symbol::new(&env, "nft_minted"),), (to, token_id));
-        token_id
+        let minted = Self::public_minted(env.clone(), buyer.clone()) + quantity;
+        if minted > sale.max_per_wallet {
+            panic!("Quantity exceeds per-wallet cap");
+        }
+        if Self::next_token_id(env.clone()) - 1 + quantity as u64 > sale.max_supply {
+            panic!("Supply cap reached");
+        }
+        
+        let cost = sale.mint_price * quantity as i128;
+        if cost > 0 {
+            token::Client::new(&env, &sale.currency).transfer(&buyer, &sale.payee, &cost);
+        }
+        Self::set_persistent(&env, &DataKey::PublicMinted(buyer.clone()), &minted);
+        
+        let mut token_ids = Vec::new(&env);
+        for _ in 0..quantity {
+            token_ids.push_back(Self::mint_token(&env, &buyer, &Bytes::new(&env)));
+        }
+        
+        env.events().publish((Symbol::new(&env, "public_minted"),), (buyer, quantity, cost));
+        token_ids
     }
     
-    /// Burn NFT (requires burner role or owner)
-    pub fn burn(env: Env, token_id: u64) {
+    /// Transfer an NFT from its owner to another account (requires owner authorization)
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u64) {
+        from.require_auth();
+        
+        let owner = Self::owner_of(env.clone(), token_id);
+        if owner != from {
+            panic!("Sender does not own token");
+        }
+        
+        Self::set_persistent(&env, &DataKey::TokenOwner(token_id), &to);
+        Self::remove_owned_token(&env, &from, token_id);
+        Self::add_owned_token(&env, &to, token_id);
+        Self::adjust_holdings(&env, &from, false);
+        Self::adjust_holdings(&env, &to, true);
+        
+        env.events().publish((Symbol::new(&env, "nft_transferred"),), (from, to, token_id));
+    }
+    
+    /// Burn NFT (requires burner role, admin, or owner)
+    pub fn burn(env: Env, caller: Address, token_id: u64) {
+        caller.require_auth();
         let burner_role = Symbol::new(&env, "burner");
-        let caller = env.current_contract_address(); // In real implementation, this would be msg.sender
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         
-        let owner: Address = env.storage().instance().get(&DataKey::TokenOwner(token_id))
+        let owner: Address = env.storage().persistent().get(&DataKey::TokenOwner(token_id))
             .unwrap_or_else(|| panic!("Token does not exist"));
         
         // Check if caller has burner role, is admin, or is owner
-        if !Self::has_role(env.clone(), caller.clone(), burner_role.clone()) 
-            && caller != admin && caller != owner {
+        if !Self::has_role(env.clone(), caller.clone(), burner_role) 
+            && !Self::is_admin(&env, &caller) && caller != owner {
             panic!("Caller does not have permission to burn");
         }
         
-        env.storage().instance().remove(&DataKey::TokenOwner(token_id));
-        env.storage().instance().remove(&DataKey::TokenMetadata(token_id));
+        env.storage().persistent().remove(&DataKey::TokenOwner(token_id));
+        env.storage().persistent().remove(&DataKey::TokenMetadata(token_id));
+        env.storage().persistent().remove(&DataKey::MetadataVersion(token_id));
+        env.storage().persistent().remove(&DataKey::MetadataFrozen(token_id));
+        Self::remove_owned_token(&env, &owner, token_id);
+        Self::adjust_holdings(&env, &owner, false);
         
         env.events().publish((Symbol::new(&env, "nft_burned"),), token_id);
     }
     
+    /// Update token metadata (requires minter role or admin)
+    pub fn update_metadata(env: Env, caller: Address, token_id: u64, metadata: Bytes) -> u32 {
+        caller.require_auth();
+        
+        let minter_role = Symbol::new(&env, "minter");
+        if !Self::has_role(env.clone(), caller.clone(), minter_role) && !Self::is_admin(&env, &caller) {
+            panic!("Caller does not have permission to update metadata");
+        }
+        
+        if !env.storage().persistent().has(&DataKey::TokenOwner(token_id)) {
+            panic!("Token does not exist");
+        }
+        if Self::is_metadata_frozen(env.clone(), token_id) {
+            panic!("Token metadata is frozen");
+        }
+        
+        let version = Self::metadata_version(env.clone(), token_id) + 1;
+        Self::set_persistent(&env, &DataKey::TokenMetadata(token_id), &metadata);
+        Self::set_persistent(&env, &DataKey::MetadataVersion(token_id), &version);
+        
+        env.events().publish((Symbol::new(&env, "metadata_updated"),), (token_id, version));
+        version
+    }
+    
+    /// Permanently freeze token metadata (admin only)
+    pub fn freeze_metadata(env: Env, caller: Address, token_id: u64) {
+        Self::require_admin(&env, &caller);
+        
+        if !env.storage().persistent().has(&DataKey::TokenOwner(token_id)) {
+            panic!("Token does not exist");
+        }
+        
+        Self::set_persistent(&env, &DataKey::MetadataFrozen(token_id), &true);
+        env.events().publish((Symbol::new(&env, "metadata_frozen"),), token_id);
+    }
+    
+    /// Check if token metadata is frozen
+    pub fn is_metadata_frozen(env: Env, token_id: u64) -> bool {
+        env.storage().persistent().get(&DataKey::MetadataFrozen(token_id)).unwrap_or(false)
+    }
+    
+    /// Get the number of times token metadata has been updated
+    pub fn metadata_version(env: Env, token_id: u64) -> u32 {
+        env.storage().persistent().get(&DataKey::MetadataVersion(token_id)).unwrap_or(0)
+    }
+    
+    /// Distribute `amount` of `token` from the caller equally across all current NFTs
+    /// (admin only). Only the evenly divisible part is pulled; the remainder stays with the caller.
+    /// This skips multi-sig approval because it only pays out the caller's own funds.
+    pub fn distribute(env: Env, caller: Address, token: Address, amount: i128) -> u32 {
+        Self::require_admin(&env, &caller);
+        
+        let token_count = Self::total_tokens(env.clone());
+        if token_count == 0 {
+            panic!("No tokens to distribute to");
+        }
+        let per_token = amount / token_count as i128;
+        if per_token <= 0 {
+            panic!("Distribution amount too small");
+        }
+        
+        let amount = per_token * token_count as i128;
+        token::Client::new(&env, &token).transfer(&caller, &env.current_contract_address(), &amount);
+        
+        let distribution_id = Self::distribution_count(env.clone()) + 1;
+        let distribution = Distribution { token: token.clone(), amount, per_token, token_count };
+        Self::set_persistent(&env, &DataKey::Distribution(distribution_id), &distribution);
+        env.storage().instance().set(&DataKey::DistributionCount, &distribution_id);
+        
+        env.events().publish((Symbol::new(&env, "distribution_created"),), (distribution_id, token, amount));
+        distribution_id
+    }
+    
+    /// Claim an account's per-NFT share of a distribution, based on holdings when it was created
+    pub fn claim_distribution(env: Env, account: Address, distribution_id: u32) -> i128 {
+        account.require_auth();
+        
+        let distribution: Distribution = env.storage().persistent()
+            .get(&DataKey::Distribution(distribution_id))
+            .unwrap_or_else(|| panic!("Distribution does not exist"));
+        
+        let claimed_key = DataKey::DistributionClaimed(distribution_id, account.clone());
+        if env.storage().persistent().has(&claimed_key) {
+            panic!("Distribution already claimed");
+        }
+        
+        let held = Self::holdings_at(&env, &account, distribution_id);
+        if held == 0 {
+            panic!("No tokens held at distribution");
+        }
+        
+        let payout = distribution.per_token * held as i128;
+        Self::set_persistent(&env, &claimed_key, &true);
+        token::Client::new(&env, &distribution.token).transfer(&env.current_contract_address(), &account, &payout);
+        
+        env.events().publish((Symbol::new(&env, "distribution_claimed"),), (distribution_id, account, payout));
+        payout
+    }
+    
+    /// Get a distribution by ID
+    pub fn get_distribution(env: Env, distribution_id: u32) -> Option<Distribution> {
+        env.storage().persistent().get(&DataKey::Distribution(distribution_id))
+    }
+    
+    /// Get the number of distributions created
+    pub fn distribution_count(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::DistributionCount).unwrap_or(0)
+    }
+    
+    /// Get the number of live tokens
+    pub fn total_tokens(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::TokenCount).unwrap_or(0)
+    }
+    
+    /// Get the number of tokens an owner currently holds
+    pub fn balance_of(env: Env, owner: Address) -> u32 {
+        Self::tokens_of(env, owner).len()
+    }
+    
+    /// Get the IDs of the tokens an owner currently holds
+    pub fn tokens_of(env: Env, owner: Address) -> Vec<u64> {
+        env.storage().persistent().get(&DataKey::OwnerTokens(owner)).unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get the number of live tokens across all owners
+    pub fn total_supply(env: Env) -> u64 {
+        Self::total_tokens(env) as u64
+    }
+    
     /// Get token owner
     pub fn owner_of(env: Env, token_id: u64) -> Address {
-        env.storage().instance().get(&DataKey::TokenOwner(token_id))
+        env.storage().persistent().get(&DataKey::TokenOwner(token_id))
             .unwrap_or_else(|| panic!("Token does not exist"))
     }
     
     /// Get token metadata
     pub fn token_metadata(env: Env, token_id: u64) -> Bytes {
-        env.storage().instance().get(&DataKey::TokenMetadata(token_id))
+        env.storage().persistent().get(&DataKey::TokenMetadata(token_id))
             .unwrap_or_else(|| panic!("Token does not exist"))
     }
     
@@ -138,16 +554,197 @@ impl AccessControlNFT {
     pub fn next_token_id(env: Env) -> u64 {
         env.storage().instance().get(&DataKey::NextTokenId).unwrap_or(1)
     }
+    
+    fn is_admin(env: &Env, account: &Address) -> bool {
+        Self::has_role(env.clone(), account.clone(), Symbol::new(env, "admin"))
+    }
+    
+    /// Require `caller` to authorize and hold the admin role, which only multi-sig
+    /// proposals can grant or revoke
+    fn require_admin(env: &Env, caller: &Address) {
+        caller.require_auth();
+        if !Self::is_admin(env, caller) {
+            panic!("Caller is not admin");
+        }
+    }
+    
+    fn require_valid_signers(signers: &Vec<Address>, threshold: u32) {
+        if threshold == 0 || threshold > signers.len() {
+            panic!("Invalid signer threshold");
+        }
+    }
+    
+    fn require_valid_sale(sale: &PublicSale) {
+        if sale.mint_price < 0 || sale.max_per_tx == 0 || sale.start_time >= sale.end_time {
+            panic!("Invalid public sale");
+        }
+    }
+    
+    fn require_not_admin_role(env: &Env, role: &Symbol) {
+        if *role == Symbol::new(env, "admin") {
+            panic!("Admin role changes require multi-sig approval");
+        }
+    }
+    
+    fn require_role_admin(env: &Env, caller: &Address, role: &Symbol) {
+        let admin_role = Self::role_admin(env.clone(), role.clone());
+        if !Self::has_role(env.clone(), caller.clone(), admin_role) {
+            panic!("Caller is not the role admin");
+        }
+    }
+    
+    fn pending_grant(env: &Env, grant_id: u32) -> PendingGrant {
+        env.storage().persistent()
+            .get(&DataKey::PendingGrant(grant_id))
+            .unwrap_or_else(|| panic!("Pending grant does not exist"))
+    }
+    
+    fn require_signer(env: &Env, signer: &Address) {
+        if !Self::admin_signers(env.clone()).contains(signer) {
+            panic!("Caller is not an admin signer");
+        }
+    }
+    
+    /// Add a signer's approval and execute the action once the threshold is reached.
+    /// Executed proposals are removed from storage.
+    fn record_approval(env: &Env, proposal_id: u32, signer: Address) {
+        let key = DataKey::AdminProposal(proposal_id);
+        let mut proposal: AdminProposal = env.storage().persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic!("Admin proposal does not exist"));
+        
+        if proposal.approvals.contains(&signer) {
+            panic!("Signer already approved");
+        }
+        
+        proposal.approvals.push_back(signer.clone());
+        env.events().publish((Symbol::new(env, "admin_action_approved"),), (proposal_id, signer));
+        
+        // Approvals from signers removed since they approved no longer count
+        let signers = Self::admin_signers(env.clone());
+        let approvals = proposal.approvals.iter().filter(|approver| signers.contains(approver)).count() as u32;
+        if approvals >= Self::admin_threshold(env.clone()) {
+            let admin_role = Symbol::new(env, "admin");
+            match proposal.action.clone() {
+                AdminAction::GrantAdmin(account) => {
+                    env.storage().instance().set(&DataKey::Roles(account.clone(), admin_role.clone()), &true);
+                    env.events().publish((Symbol::new(env, "role_granted"),), (account, admin_role));
+                }
+                AdminAction::RevokeAdmin(account) => {
+                    env.storage().instance().set(&DataKey::Roles(account.clone(), admin_role.clone()), &false);
+                    env.events().publish((Symbol::new(env, "role_revoked"),), (account, admin_role));
+                }
+                AdminAction::SetSigners(signers, threshold) => {
+                    env.storage().instance().set(&DataKey::AdminSigners, &signers);
+                    env.storage().instance().set(&DataKey::AdminThreshold, &threshold);
+                    env.events().publish((Symbol::new(env, "admin_signers_changed"),), (signers, threshold));
+                }
+                AdminAction::SetPublicSale(sale) => {
+                    env.storage().instance().set(&DataKey::PublicSale, &sale);
+                }
+                AdminAction::SetRoleAdmin(role, role_admin) => {
+                    env.storage().instance().set(&DataKey::RoleAdmin(role.clone()), &role_admin);
+                    env.events().publish((Symbol::new(env, "role_admin_changed"),), (role, role_admin));
+                }
+                AdminAction::SetGrantDelay(role, delay) => {
+                    env.storage().instance().set(&DataKey::GrantDelay(role), &delay);
+                }
+            }
+            env.storage().persistent().remove(&key);
+            env.events().publish((Symbol::new(env, "admin_action_executed"),), proposal_id);
+        } else {
+            Self::set_persistent(env, &key, &proposal);
+        }
+    }
+    
+    /// Write a per-token entry to persistent storage and extend its TTL, along with the
+    /// contract instance that holds the collection config
+    fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, TOKEN_TTL_THRESHOLD, TOKEN_TTL_EXTEND);
+        env.storage().instance().extend_ttl(TOKEN_TTL_THRESHOLD, TOKEN_TTL_EXTEND);
+    }
+    
+    fn mint_token(env: &Env, to: &Address, metadata: &Bytes) -> u64 {
+        let token_id: u64 = env.storage().instance().get(&DataKey::NextTokenId).unwrap_or(1);
+        
+        Self::set_persistent(env, &DataKey::TokenOwner(token_id), to);
+        Self::set_persistent(env, &DataKey::TokenMetadata(token_id), metadata);
+        Self::add_owned_token(env, to, token_id);
+        env.storage().instance().set(&DataKey::NextTokenId, &(token_id + 1));
+        Self::adjust_holdings(env, to, true);
+        
+        env.events().publish((Symbol::new(env, "nft_minted"),), (to.clone(), token_id));
+        token_id
+    }
+    
+    fn add_owned_token(env: &Env, owner: &Address, token_id: u64) {
+        let mut tokens = Self::tokens_of(env.clone(), owner.clone());
+        tokens.push_back(token_id);
+        Self::set_persistent(env, &DataKey::OwnerTokens(owner.clone()), &tokens);
+    }
+    
+    fn remove_owned_token(env: &Env, owner: &Address, token_id: u64) {
+        let mut tokens = Self::tokens_of(env.clone(), owner.clone());
+        if let Some(index) = tokens.first_index_of(token_id) {
+            tokens.remove(index);
+        }
+        if tokens.is_empty() {
+            env.storage().persistent().remove(&DataKey::OwnerTokens(owner.clone()));
+        } else {
+            Self::set_persistent(env, &DataKey::OwnerTokens(owner.clone()), &tokens);
+        }
+    }
+    
+    /// Record an owner gaining or losing a token. The owner's new balance, read from
+    /// `OwnerTokens`, is checkpointed against the distribution count.
+    fn adjust_holdings(env: &Env, owner: &Address, gained: bool) {
+        let distribution_count = Self::distribution_count(env.clone());
+        let mut checkpoints: Vec<(u32, u32)> = env.storage().persistent()
+            .get(&DataKey::HolderCheckpoints(owner.clone()))
+            .unwrap_or(Vec::new(env));
+        
+        let updated = Self::balance_of(env.clone(), owner.clone());
+        
+        match checkpoints.last() {
+            Some((at, _)) if at == distribution_count => {
+                checkpoints.set(checkpoints.len() - 1, (distribution_count, updated));
+            }
+            _ => checkpoints.push_back((distribution_count, updated)),
+        }
+        Self::set_persistent(env, &DataKey::HolderCheckpoints(owner.clone()), &checkpoints);
+        
+        let total = Self::total_tokens(env.clone());
+        let total = if gained { total + 1 } else { total - 1 };
+        env.storage().instance().set(&DataKey::TokenCount, &total);
+    }
+    
+    /// Number of tokens an owner held when the given distribution was created
+    fn holdings_at(env: &Env, owner: &Address, distribution_id: u32) -> u32 {
+        let checkpoints: Vec<(u32, u32)> = env.storage().persistent()
+            .get(&DataKey::HolderCheckpoints(owner.clone()))
+            .unwrap_or(Vec::new(env));
+        
+        let mut held = 0;
+        for (at, count) in checkpoints.iter() {
+            if at >= distribution_id {
+                break;
+            }
+            held = count;
+        }
+        held
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env, Bytes};
+    use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env, Bytes, Vec};
 
     #[test]
     fn test_access_control_nft() {
         let env = Env::default();
+        env.mock_all_auths();
         let contract_id = env.register_contract(None, AccessControlNFT);
         let client = AccessControlNFTClient::new(&env, &contract_id);
         
@@ -156,23 +753,583 @@ mod tests {
         let user = Address::generate(&env);
         
         // Initialize contract
-        client.initialize(&admin, &Symbol::new(&env, "AccessNFT"), &Symbol::new(&env, "ANFT"));
+        client.initialize(
+            &admin,
+            &Symbol::new(&env, "AccessNFT"),
+            &Symbol::new(&env, "ANFT"),
+            &Vec::from_array(&env, [admin.clone()]),
+            &1,
+        );
         
         // Test role management
         let minter_role = Symbol::new(&env, "minter");
         assert!(!client.has_role(&minter, &minter_role));
         
-        client.grant_role(&minter, &minter_role);
+        client.grant_role(&admin, &minter, &minter_role);
         assert!(client.has_role(&minter, &minter_role));
         
         // Test NFT minting
         let metadata = Bytes::from_array(&env, &[1, 2, 3]);
-        let token_id = client.mint(&user, &metadata);
+        let token_id = client.mint(&minter, &user, &metadata);
         assert_eq!(token_id, 1);
         assert_eq!(client.owner_of(&token_id), user);
         
         // Test role revocation
-        client.revoke_role(&minter, &minter_role);
+        client.revoke_role(&admin, &minter, &minter_role);
         assert!(!client.has_role(&minter, &minter_role));
     }
+    
+    fn setup_with_token(env: &Env) -> (AccessControlNFTClient<'_>, Address, u64) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AccessControlNFT);
+        let client = AccessControlNFTClient::new(env, &contract_id);
+        
+        let admin = Address::generate(env);
+        client.initialize(
+            &admin,
+            &Symbol::new(env, "AccessNFT"),
+            &Symbol::new(env, "ANFT"),
+            &Vec::from_array(env, [admin.clone()]),
+            &1,
+        );
+        
+        let token_id = client.mint(&admin, &Address::generate(env), &Bytes::from_array(env, &[1]));
+        
+        (client, admin, token_id)
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller does not have minter role")]
+    fn test_mint_by_non_minter_rejected() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        
+        // Holding the role on the contract's own address no longer lets anyone mint
+        client.grant_role(&admin, &client.address, &Symbol::new(&env, "minter"));
+        client.mint(&Address::generate(&env), &Address::generate(&env), &Bytes::from_array(&env, &[2]));
+    }
+    
+    #[test]
+    fn test_granted_minter_can_mint() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let minter = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        
+        client.grant_role(&admin, &minter, &Symbol::new(&env, "minter"));
+        let token_id = client.mint(&minter, &recipient, &Bytes::from_array(&env, &[2]));
+        
+        assert_eq!(client.owner_of(&token_id), recipient);
+    }
+    
+    #[test]
+    fn test_owner_can_burn() {
+        let env = Env::default();
+        let (client, _admin, token_id) = setup_with_token(&env);
+        let owner = client.owner_of(&token_id);
+        
+        client.burn(&owner, &token_id);
+        assert_eq!(client.balance_of(&owner), 0);
+        assert_eq!(client.total_tokens(), 0);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller does not have permission to burn")]
+    fn test_burn_by_non_owner_rejected() {
+        let env = Env::default();
+        let (client, _admin, token_id) = setup_with_token(&env);
+        
+        client.burn(&Address::generate(&env), &token_id);
+    }
+    
+    #[test]
+    fn test_tokens_outlive_default_ttl() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let holders = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+        for i in 0..30u8 {
+            client.mint(&admin, &holders[i as usize % 3], &Bytes::from_array(&env, &[i]));
+        }
+        
+        // Each token is its own persistent entry rather than part of the instance
+        env.as_contract(&client.address, || {
+            assert!(env.storage().persistent().has(&DataKey::TokenOwner(2)));
+            assert!(!env.storage().instance().has(&DataKey::TokenOwner(2)));
+        });
+        
+        // Well past the default entry TTL, but within the TTL extended on write
+        env.ledger().with_mut(|li| li.sequence_number += 20 * DAY_IN_LEDGERS);
+        for i in 0..30u8 {
+            let token_id = i as u64 + 2;
+            assert_eq!(client.owner_of(&token_id), holders[i as usize % 3]);
+            assert_eq!(client.token_metadata(&token_id), Bytes::from_array(&env, &[i]));
+        }
+    }
+    
+    #[test]
+    fn test_enumerate_tokens_across_transfer_and_burn() {
+        let env = Env::default();
+        let (client, admin, first) = setup_with_token(&env);
+        let alice = client.owner_of(&first);
+        let bob = Address::generate(&env);
+        let second = client.mint(&admin, &alice, &Bytes::from_array(&env, &[2]));
+        let third = client.mint(&admin, &bob, &Bytes::from_array(&env, &[3]));
+        assert_eq!(client.total_supply(), 3);
+        assert_eq!(client.tokens_of(&alice), Vec::from_array(&env, [first, second]));
+        
+        client.transfer(&alice, &bob, &first);
+        assert_eq!(client.tokens_of(&alice), Vec::from_array(&env, [second]));
+        assert_eq!(client.tokens_of(&bob), Vec::from_array(&env, [third, first]));
+        assert_eq!(client.balance_of(&bob), 2);
+        
+        client.burn(&bob, &third);
+        assert_eq!(client.tokens_of(&bob), Vec::from_array(&env, [first]));
+        assert_eq!(client.balance_of(&bob), 1);
+        assert_eq!(client.total_supply(), 2);
+    }
+    
+    #[test]
+    fn test_transfer_moves_ownership() {
+        let env = Env::default();
+        let (client, _admin, token_id) = setup_with_token(&env);
+        let owner = client.owner_of(&token_id);
+        let recipient = Address::generate(&env);
+        
+        client.transfer(&owner, &recipient, &token_id);
+        
+        assert_eq!(client.owner_of(&token_id), recipient);
+        assert_eq!(client.balance_of(&owner), 0);
+        assert_eq!(client.balance_of(&recipient), 1);
+        assert_eq!(client.total_tokens(), 1);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Sender does not own token")]
+    fn test_transfer_by_non_owner_rejected() {
+        let env = Env::default();
+        let (client, _admin, token_id) = setup_with_token(&env);
+        
+        client.transfer(&Address::generate(&env), &Address::generate(&env), &token_id);
+    }
+    
+    #[test]
+    fn test_update_metadata_versions() {
+        let env = Env::default();
+        let (client, admin, token_id) = setup_with_token(&env);
+        assert_eq!(client.metadata_version(&token_id), 0);
+        
+        client.update_metadata(&admin, &token_id, &Bytes::from_array(&env, &[2]));
+        let version = client.update_metadata(&admin, &token_id, &Bytes::from_array(&env, &[3]));
+        
+        assert_eq!(version, 2);
+        assert_eq!(client.metadata_version(&token_id), 2);
+        assert_eq!(client.token_metadata(&token_id), Bytes::from_array(&env, &[3]));
+    }
+    
+    #[test]
+    #[should_panic(expected = "Token metadata is frozen")]
+    fn test_update_frozen_metadata_rejected() {
+        let env = Env::default();
+        let (client, admin, token_id) = setup_with_token(&env);
+        
+        client.freeze_metadata(&admin, &token_id);
+        client.update_metadata(&admin, &token_id, &Bytes::from_array(&env, &[2]));
+    }
+    
+    #[test]
+    fn test_distribution_equal_per_token_claims() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let holder_a = client.owner_of(&1);
+        let holder_b = Address::generate(&env);
+        client.mint(&admin, &holder_b, &Bytes::from_array(&env, &[2]));
+        client.mint(&admin, &holder_b, &Bytes::from_array(&env, &[3]));
+        
+        let payout = env.register_stellar_asset_contract(Address::generate(&env));
+        token::StellarAssetClient::new(&env, &payout).mint(&admin, &300);
+        let distribution_id = client.distribute(&admin, &payout, &300);
+        
+        // Tokens minted after the snapshot do not share in it
+        client.mint(&admin, &holder_a, &Bytes::from_array(&env, &[4]));
+        
+        assert_eq!(client.claim_distribution(&holder_a, &distribution_id), 100);
+        assert_eq!(client.claim_distribution(&holder_b, &distribution_id), 200);
+        assert_eq!(token::Client::new(&env, &payout).balance(&holder_a), 100);
+        assert_eq!(token::Client::new(&env, &payout).balance(&holder_b), 200);
+    }
+    
+    #[test]
+    fn test_distribution_leaves_remainder_with_admin() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let holder = Address::generate(&env);
+        client.mint(&admin, &holder, &Bytes::from_array(&env, &[2]));
+        client.mint(&admin, &holder, &Bytes::from_array(&env, &[3]));
+        
+        let payout = env.register_stellar_asset_contract(Address::generate(&env));
+        token::StellarAssetClient::new(&env, &payout).mint(&admin, &301);
+        let distribution_id = client.distribute(&admin, &payout, &301);
+        
+        assert_eq!(client.get_distribution(&distribution_id).unwrap().amount, 300);
+        assert_eq!(token::Client::new(&env, &payout).balance(&admin), 1);
+        env.as_contract(&client.address, || {
+            assert!(env.storage().persistent().has(&DataKey::Distribution(distribution_id)));
+            assert!(env.storage().persistent().has(&DataKey::HolderCheckpoints(holder.clone())));
+            assert!(!env.storage().instance().has(&DataKey::HolderCheckpoints(holder.clone())));
+        });
+    }
+    
+    #[test]
+    #[should_panic(expected = "Distribution already claimed")]
+    fn test_distribution_double_claim_rejected() {
+        let env = Env::default();
+        let (client, admin, token_id) = setup_with_token(&env);
+        let holder = client.owner_of(&token_id);
+        
+        let payout = env.register_stellar_asset_contract(Address::generate(&env));
+        token::StellarAssetClient::new(&env, &payout).mint(&admin, &100);
+        let distribution_id = client.distribute(&admin, &payout, &100);
+        
+        client.claim_distribution(&holder, &distribution_id);
+        client.claim_distribution(&holder, &distribution_id);
+    }
+    
+    #[test]
+    fn test_admin_grant_requires_threshold() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AccessControlNFT);
+        let client = AccessControlNFTClient::new(&env, &contract_id);
+        
+        let admin = Address::generate(&env);
+        let signer2 = Address::generate(&env);
+        let signer3 = Address::generate(&env);
+        let new_admin = Address::generate(&env);
+        client.initialize(
+            &admin,
+            &Symbol::new(&env, "AccessNFT"),
+            &Symbol::new(&env, "ANFT"),
+            &Vec::from_array(&env, [admin.clone(), signer2.clone(), signer3.clone()]),
+            &2,
+        );
+        let admin_role = Symbol::new(&env, "admin");
+        
+        // A single approval is not enough
+        let proposal_id = client.propose_admin_action(&admin, &AdminAction::GrantAdmin(new_admin.clone()));
+        assert!(!client.has_role(&new_admin, &admin_role));
+        assert_eq!(client.get_admin_proposal(&proposal_id).unwrap().approvals.len(), 1);
+        
+        // The second signer reaches the 2-of-3 threshold, which executes and clears the proposal
+        client.approve_admin_action(&signer2, &proposal_id);
+        assert!(client.has_role(&new_admin, &admin_role));
+        assert!(client.get_admin_proposal(&proposal_id).is_none());
+    }
+    
+    #[test]
+    #[should_panic(expected = "Admin proposal does not exist")]
+    fn test_executed_proposal_cannot_be_approved() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        
+        let proposal_id = client.propose_admin_action(&admin, &AdminAction::GrantAdmin(Address::generate(&env)));
+        client.approve_admin_action(&admin, &proposal_id);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller does not have minter role")]
+    fn test_revoked_admin_loses_admin_powers() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        
+        client.propose_admin_action(&admin, &AdminAction::RevokeAdmin(admin.clone()));
+        assert!(!client.has_role(&admin, &Symbol::new(&env, "admin")));
+        client.mint(&admin, &Address::generate(&env), &Bytes::from_array(&env, &[2]));
+    }
+    
+    #[test]
+    fn test_signer_set_changes_through_proposal() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let signer2 = Address::generate(&env);
+        let signers = Vec::from_array(&env, [admin.clone(), signer2.clone()]);
+        
+        client.propose_admin_action(&admin, &AdminAction::SetSigners(signers.clone(), 2));
+        assert_eq!(client.admin_signers(), signers);
+        assert_eq!(client.admin_threshold(), 2);
+        
+        // The new 2-of-2 set now has to agree
+        let new_admin = Address::generate(&env);
+        let proposal_id = client.propose_admin_action(&admin, &AdminAction::GrantAdmin(new_admin.clone()));
+        assert!(!client.has_role(&new_admin, &Symbol::new(&env, "admin")));
+        client.approve_admin_action(&signer2, &proposal_id);
+        assert!(client.has_role(&new_admin, &Symbol::new(&env, "admin")));
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller is not admin")]
+    fn test_non_admin_cannot_freeze_metadata() {
+        let env = Env::default();
+        let (client, _admin, token_id) = setup_with_token(&env);
+        
+        client.freeze_metadata(&Address::generate(&env), &token_id);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Admin role changes require multi-sig approval")]
+    fn test_direct_admin_grant_rejected() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        
+        client.grant_role(&admin, &Address::generate(&env), &Symbol::new(&env, "admin"));
+    }
+    
+    fn setup_minter_admin(env: &Env) -> (AccessControlNFTClient<'_>, Address) {
+        let (client, admin, _) = setup_with_token(env);
+        let minter_admin_role = Symbol::new(env, "minter_admin");
+        let minter_admin = Address::generate(env);
+        
+        client.propose_admin_action(&admin, &AdminAction::SetRoleAdmin(Symbol::new(env, "minter"), minter_admin_role.clone()));
+        client.grant_role(&admin, &minter_admin, &minter_admin_role);
+        (client, minter_admin)
+    }
+    
+    #[test]
+    fn test_role_admin_grants_delegated_role() {
+        let env = Env::default();
+        let (client, minter_admin) = setup_minter_admin(&env);
+        let minter_role = Symbol::new(&env, "minter");
+        let minter = Address::generate(&env);
+        assert_eq!(client.role_admin(&minter_role), Symbol::new(&env, "minter_admin"));
+        assert_eq!(client.role_admin(&Symbol::new(&env, "burner")), Symbol::new(&env, "admin"));
+        
+        client.grant_role(&minter_admin, &minter, &minter_role);
+        assert!(client.has_role(&minter, &minter_role));
+        
+        client.revoke_role(&minter_admin, &minter, &minter_role);
+        assert!(!client.has_role(&minter, &minter_role));
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller is not the role admin")]
+    fn test_unrelated_account_cannot_grant_delegated_role() {
+        let env = Env::default();
+        let (client, _minter_admin) = setup_minter_admin(&env);
+        let outsider = Address::generate(&env);
+        
+        client.grant_role(&outsider, &outsider, &Symbol::new(&env, "minter"));
+    }
+    
+    #[test]
+    #[should_panic(expected = "Grant is not yet active")]
+    fn test_activate_grant_before_eta_rejected() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        client.propose_admin_action(&admin, &AdminAction::SetGrantDelay(Symbol::new(&env, "minter"), 3600));
+        
+        let grant_id = client.announce_grant(&admin, &Address::generate(&env), &Symbol::new(&env, "minter"));
+        env.ledger().with_mut(|li| li.timestamp += 3599);
+        client.activate_grant(&grant_id);
+    }
+    
+    #[test]
+    fn test_activate_grant_after_eta() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let account = Address::generate(&env);
+        let minter_role = Symbol::new(&env, "minter");
+        client.propose_admin_action(&admin, &AdminAction::SetGrantDelay(minter_role.clone(), 3600));
+        
+        let grant_id = client.announce_grant(&admin, &account, &minter_role);
+        assert!(!client.has_role(&account, &minter_role));
+        
+        env.ledger().with_mut(|li| li.timestamp += 3600);
+        client.activate_grant(&grant_id);
+        assert!(client.has_role(&account, &minter_role));
+        assert!(client.get_pending_grant(&grant_id).is_none());
+    }
+    
+    #[test]
+    #[should_panic(expected = "Pending grant does not exist")]
+    fn test_grant_cannot_be_activated_twice() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let minter_role = Symbol::new(&env, "minter");
+        client.propose_admin_action(&admin, &AdminAction::SetGrantDelay(minter_role.clone(), 3600));
+        
+        let grant_id = client.announce_grant(&admin, &Address::generate(&env), &minter_role);
+        env.ledger().with_mut(|li| li.timestamp += 3600);
+        client.activate_grant(&grant_id);
+        client.activate_grant(&grant_id);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Role grant requires announcement")]
+    fn test_timelocked_role_cannot_be_granted_directly() {
+        let env = Env::default();
+        let (client, admin, _) = setup_with_token(&env);
+        let minter_role = Symbol::new(&env, "minter");
+        client.propose_admin_action(&admin, &AdminAction::SetGrantDelay(minter_role.clone(), 3600));
+        
+        client.grant_role(&admin, &Address::generate(&env), &minter_role);
+    }
+    
+    #[test]
+    fn test_role_admin_announces_timelocked_grant() {
+        let env = Env::default();
+        let (client, minter_admin) = setup_minter_admin(&env);
+        let minter_role = Symbol::new(&env, "minter");
+        let account = Address::generate(&env);
+        
+        // The delay only covers "minter"; other roles can still be granted at once
+        let admin = client.admin_signers().get(0).unwrap();
+        client.propose_admin_action(&admin, &AdminAction::SetGrantDelay(minter_role.clone(), 3600));
+        assert_eq!(client.grant_delay(&Symbol::new(&env, "burner")), 0);
+        
+        let grant_id = client.announce_grant(&minter_admin, &account, &minter_role);
+        env.ledger().with_mut(|li| li.timestamp += 3600);
+        client.activate_grant(&grant_id);
+        assert!(client.has_role(&account, &minter_role));
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller is not the role admin")]
+    fn test_outsider_cannot_announce_grant() {
+        let env = Env::default();
+        let (client, _minter_admin) = setup_minter_admin(&env);
+        let outsider = Address::generate(&env);
+        
+        client.announce_grant(&outsider, &outsider, &Symbol::new(&env, "minter"));
+    }
+    
+    fn announced_minter_grant(env: &Env) -> (AccessControlNFTClient<'_>, Address, Address, u32) {
+        let (client, minter_admin) = setup_minter_admin(env);
+        let admin = client.admin_signers().get(0).unwrap();
+        let minter_role = Symbol::new(env, "minter");
+        client.propose_admin_action(&admin, &AdminAction::SetGrantDelay(minter_role.clone(), 3600));
+        
+        let grant_id = client.announce_grant(&minter_admin, &Address::generate(env), &minter_role);
+        (client, admin, minter_admin, grant_id)
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller is not the role admin")]
+    fn test_grant_lapses_when_announcer_loses_role_admin() {
+        let env = Env::default();
+        let (client, admin, minter_admin, grant_id) = announced_minter_grant(&env);
+        
+        client.revoke_role(&admin, &minter_admin, &Symbol::new(&env, "minter_admin"));
+        env.ledger().with_mut(|li| li.timestamp += 3600);
+        client.activate_grant(&grant_id);
+    }
+    
+    #[test]
+    fn test_cancel_grant() {
+        let env = Env::default();
+        let (client, admin, minter_admin, grant_id) = announced_minter_grant(&env);
+        
+        client.cancel_grant(&minter_admin, &grant_id);
+        assert!(client.get_pending_grant(&grant_id).is_none());
+        
+        // The role admin can cancel grants someone else announced
+        let grant_id = client.announce_grant(&minter_admin, &Address::generate(&env), &Symbol::new(&env, "minter"));
+        client.grant_role(&admin, &admin, &Symbol::new(&env, "minter_admin"));
+        client.cancel_grant(&admin, &grant_id);
+        assert!(client.get_pending_grant(&grant_id).is_none());
+    }
+    
+    #[test]
+    #[should_panic(expected = "Caller is not the role admin")]
+    fn test_outsider_cannot_cancel_grant() {
+        let env = Env::default();
+        let (client, _admin, _minter_admin, grant_id) = announced_minter_grant(&env);
+        
+        client.cancel_grant(&Address::generate(&env), &grant_id);
+    }
+    
+    fn setup_public_sale(env: &Env) -> (AccessControlNFTClient<'_>, Address, Address, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AccessControlNFT);
+        let client = AccessControlNFTClient::new(env, &contract_id);
+        
+        let admin = Address::generate(env);
+        client.initialize(
+            &admin,
+            &Symbol::new(env, "AccessNFT"),
+            &Symbol::new(env, "ANFT"),
+            &Vec::from_array(env, [admin.clone()]),
+            &1,
+        );
+        
+        let currency = env.register_stellar_asset_contract(Address::generate(env));
+        let buyer = Address::generate(env);
+        token::StellarAssetClient::new(env, &currency).mint(&buyer, &1000);
+        
+        client.propose_admin_action(&admin, &AdminAction::SetPublicSale(PublicSale {
+            currency: currency.clone(),
+            payee: admin.clone(),
+            mint_price: 10,
+            start_time: 100,
+            end_time: 200,
+            max_per_tx: 3,
+            max_per_wallet: 4,
+            max_supply: 10,
+        }));
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        
+        (client, admin, currency, buyer)
+    }
+    
+    #[test]
+    fn test_public_mint_within_caps() {
+        let env = Env::default();
+        let (client, admin, currency, buyer) = setup_public_sale(&env);
+        
+        let token_ids = client.public_mint(&buyer, &3);
+        assert_eq!(token_ids.len(), 3);
+        assert_eq!(client.owner_of(&token_ids.get(2).unwrap()), buyer);
+        assert_eq!(client.public_minted(&buyer), 3);
+        env.as_contract(&client.address, || {
+            assert!(env.storage().persistent().has(&DataKey::PublicMinted(buyer.clone())));
+        });
+        assert_eq!(token::Client::new(&env, &currency).balance(&buyer), 970);
+        assert_eq!(token::Client::new(&env, &currency).balance(&admin), 30);
+    }
+    
+    #[test]
+    fn test_public_sale_requires_threshold() {
+        let env = Env::default();
+        let (client, admin, currency, _buyer) = setup_public_sale(&env);
+        let signer2 = Address::generate(&env);
+        client.propose_admin_action(&admin, &AdminAction::SetSigners(Vec::from_array(&env, [admin.clone(), signer2.clone()]), 2));
+        
+        // A lone admin can no longer redirect sale proceeds
+        let mut sale = client.public_sale().unwrap();
+        sale.payee = Address::generate(&env);
+        let proposal_id = client.propose_admin_action(&admin, &AdminAction::SetPublicSale(sale.clone()));
+        assert_eq!(client.public_sale().unwrap().payee, admin);
+        
+        client.approve_admin_action(&signer2, &proposal_id);
+        assert_eq!(client.public_sale().unwrap().payee, sale.payee);
+        assert_eq!(client.public_sale().unwrap().currency, currency);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Quantity exceeds per-wallet cap")]
+    fn test_public_mint_wallet_cap() {
+        let env = Env::default();
+        let (client, _admin, _currency, buyer) = setup_public_sale(&env);
+        
+        client.public_mint(&buyer, &3);
+        client.public_mint(&buyer, &2);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Public sale is not active")]
+    fn test_public_mint_after_window() {
+        let env = Env::default();
+        let (client, _admin, _currency, buyer) = setup_public_sale(&env);
+        
+        env.ledger().with_mut(|li| li.timestamp = 200);
+        client.public_mint(&buyer, &1);
+    }
 }
@@ -1,7 +1,8 @@
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Env, Vec, 
+    contract, contractimpl, contracttype, Address, BytesN, Env, IntoVal, Val, Vec, 
     symbol_short, token, contracterror
 };
+use crate::access_control_nft::AccessControlNFTClient;
 
 /// Status of a marketplace listing
 #[contracttype]
@@ -28,6 +29,50 @@ pub struct Listing {
     pub expires_at: u64,
 }
 
+/// Creator royalty paid to `recipient` out of a listing's sale price
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Royalty {
+    pub recipient: Address,
+    pub bps: u32,
+}
+
+/// A volume-based fee tier: sellers whose cumulative sales volume reaches
+/// `min_volume` pay `fee_bps` instead of the flat marketplace fee
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTier {
+    pub min_volume: i128,
+    pub fee_bps: u32,
+}
+
+/// Buyer loyalty program: buyers earn `points_per_purchase` on every purchase and
+/// each redeemed point rebates `rebate_bps_per_point` of the fee (zero disables it)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoyaltyConfig {
+    pub points_per_purchase: u64,
+    pub rebate_bps_per_point: u32,
+}
+
+/// Bidding state for a timed auction listing; the listing `price` is the reserve.
+/// `highest_bidder` is only meaningful once `has_bid` is set.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub highest_bid: i128,
+    pub highest_bidder: Address,
+    pub has_bid: bool,
+}
+
+/// A buyer's escrowed offer on a listing, valid until `expires_at`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Offer {
+    pub amount: i128,
+    pub expires_at: u64,
+}
+
 /// Marketplace events
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -37,6 +82,14 @@ pub enum MarketplaceEvent {
     ListingPurchased(u64, Address, Address, i128), // listing_id, seller, buyer, price
     ListingExpired(u64), // listing_id
     OwnershipTransferred(Address, Address), // previous_owner, new_owner
+    BidPlaced(u64, Address, i128), // listing_id, bidder, amount
+    AuctionSettled(u64, Address, i128), // listing_id, winner, winning_bid
+    OfferMade(u64, Address, i128), // listing_id, buyer, amount
+    OfferAccepted(u64, Address, i128), // listing_id, buyer, amount
+    OfferCancelled(u64, Address), // listing_id, buyer
+    RoyaltyPaid(u64, Address, i128), // listing_id, recipient, amount
+    BidReclaimed(u64, Address, i128), // listing_id, bidder, amount
+    Upgraded(BytesN<32>), // new_wasm_hash
 }
 
 /// Marketplace error types
@@ -53,6 +106,28 @@ pub enum MarketplaceError {
     ListingExpired = 7,
     AlreadyInitialized = 8,
     NotInitialized = 9,
+    BatchTooLarge = 10,
+    InvalidPayees = 11,
+    InitializationLocked = 12,
+    ContractShutdown = 13,
+    NotShutdown = 14,
+    BidTooLow = 15,
+    AuctionNotEnded = 16,
+    AuctionListing = 17,
+    NotAuction = 18,
+    AuctionHasBids = 19,
+    OfferNotFound = 20,
+    OfferExpired = 21,
+    OfferExists = 22,
+    InvalidRoyalty = 23,
+    CurrencyNotAccepted = 25,
+    TooManyOffers = 26,
+    OfferTooLow = 27,
+    SelfTrade = 29,
+    InvalidFee = 30,
+    ArithmeticOverflow = 31,
+    ListingNotExpired = 32,
+    RoyaltyLocked = 33,
 }
 
 /// Storage keys for the marketplace
@@ -66,8 +141,60 @@ pub enum DataKey {
     MarketplaceFee,
     TreasuryAddress,
     Initialized,
+    FeeTiers,
+    SellerVolume(Address, Address), // (seller, currency) -> cumulative sales volume
+    LoyaltyConfig,
+    BuyerPoints(Address),
+    CollectionListings(Address), // token_contract -> [(price, listing_id, expires_at)] of the cheapest open fixed-price listings
+    Payees(u64),                 // listing_id -> [(payee, share_bps)], absent when the seller is paid directly
+    InitVersion,
+    Shutdown,
+    Auction(u64),                    // listing_id -> Auction
+    PendingRefund(Address, Address), // (account, currency) -> escrowed funds owed after a failed transfer
+    Offer(u64, Address),             // (listing_id, buyer) -> Offer
+    OfferBuyers(u64),                // listing_id -> buyers with outstanding offers
+    AcceptedCurrency(Address),       // currency -> true while on the allowlist
+    AcceptedCurrencyCount,           // number of allowlisted currencies; 0 accepts any
+    Royalty(u64),                    // listing_id -> Royalty, absent when the listing pays none
+    Escrowed(Address),               // currency -> bids, offers, and refunds held for their owners
 }
 
+/// Maximum number of listings processed in a single batch call
+const MAX_BATCH_SIZE: u32 = 50;
+
+/// Maximum number of outstanding offers on a listing, bounding the refunds `accept_offer` makes
+const MAX_OFFERS_PER_LISTING: u32 = 20;
+
+/// Smallest offer accepted, in basis points of the listing price, so the offer slots can't
+/// be filled with dust
+const MIN_OFFER_BPS: i128 = 1000;
+
+/// Maximum number of payees splitting a listing's proceeds
+const MAX_PAYEES: u32 = 10;
+
+/// Maximum number of listing IDs scanned by a single page query
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of listings kept in a collection's price index, bounding the floor and sweep
+/// lookups. Pricier listings beyond it are still created, just not indexed.
+const MAX_COLLECTION_LISTINGS: u32 = 100;
+
+/// Initialization version at which the marketplace configuration is locked for good
+const FINAL_INIT_VERSION: u32 = 3;
+
+/// Seconds after an auction ends during which it can be settled before the winning
+/// bidder may reclaim their bid instead
+const SETTLEMENT_GRACE_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+/// Roughly one day of ledgers at a 5 second close time
+const DAY_IN_LEDGERS: u32 = 17280;
+
+/// Listings, their auction, offer and royalty records, seller listing indexes and owed refunds
+/// live 30 days past their last write or bump, so an open listing or escrowed funds can't be
+/// archived; the TTL is only re-extended once it drops below 29 days, keeping repeated writes cheap
+const LISTING_TTL_EXTEND: u32 = 30 * DAY_IN_LEDGERS;
+const LISTING_TTL_THRESHOLD: u32 = LISTING_TTL_EXTEND - DAY_IN_LEDGERS;
+
 #[contract]
 pub struct AdvancedMarketplace;
 
@@ -79,6 +206,8 @@ impl AdvancedMarketplace {
         owner: Address,
         treasury: Address,
         marketplace_fee: u32, // Fee in basis points (100 = 1%)
+        fee_tiers: Vec<FeeTier>,
+        loyalty: LoyaltyConfig,
     ) -> Result<(), MarketplaceError> {
         if env.storage().instance().has(&DataKey::Initialized) {
             return Err(MarketplaceError::AlreadyInitialized);
@@ -87,15 +216,53 @@ impl AdvancedMarketplace {
         owner.require_auth();
         
         env.storage().instance().set(&DataKey::Owner, &owner);
-        env.storage().instance().set(&DataKey::TreasuryAddress, &treasury);
-        env.storage().instance().set(&DataKey::MarketplaceFee, &marketplace_fee);
+        Self::store_config(&env, treasury, marketplace_fee, fee_tiers, loyalty)?;
         env.storage().instance().set(&DataKey::NextListingId, &1u64);
         env.storage().instance().set(&DataKey::Initialized, &true);
+        env.storage().instance().set(&DataKey::InitVersion, &1u32);
+
+        Ok(())
+    }
+
+    /// Redo the configuration at a higher `version` (owner only), until the final version locks it
+    pub fn reinitialize(
+        env: Env,
+        version: u32,
+        treasury: Address,
+        marketplace_fee: u32,
+        fee_tiers: Vec<FeeTier>,
+        loyalty: LoyaltyConfig,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        let current = Self::init_version(env.clone());
+        if current >= FINAL_INIT_VERSION || version <= current || version > FINAL_INIT_VERSION {
+            return Err(MarketplaceError::InitializationLocked);
+        }
+
+        Self::store_config(&env, treasury, marketplace_fee, fee_tiers, loyalty)?;
+        env.storage().instance().set(&DataKey::InitVersion, &version);
+        Ok(())
+    }
 
+    /// Lock initialization at the final version (owner only)
+    pub fn lock_initialization(env: Env) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        env.storage().instance().set(&DataKey::InitVersion, &FINAL_INIT_VERSION);
         Ok(())
     }
 
-    /// Create a new listing
+    /// Get the current initialization version (0 before `initialize`)
+    pub fn init_version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::InitVersion).unwrap_or(0)
+    }
+
+    /// Create a new listing, escrowing the NFT in the marketplace until it sells or is withdrawn
     pub fn create_listing(
         env: Env,
         seller: Address,
@@ -105,247 +272,2603 @@ impl AdvancedMarketplace {
         currency: Address,
         duration: u64, // Duration in seconds
     ) -> Result<u64, MarketplaceError> {
-        Self::require_initialized(&env)?;
+        Self::require_trading(&env)?;
         seller.require_auth();
 
         if price <= 0 {
             return Err(MarketplaceError::InvalidPrice);
         }
+        if !Self::is_accepted_currency(env.clone(), currency.clone()) {
+            return Err(MarketplaceError::CurrencyNotAccepted);
+        }
 
-        let listing_id = env.storage().instance().get(&DataKey::NextListingId).unwrap_or(1u64);
-        
         let current_time = env.ledger().timestamp();
         let expires_at = current_time + duration;
 
-        let listing = Listing {
-            id: listing_id,
+        let listing_id = Self::store_listing(&env, Listing {
+            id: 0,
             seller: seller.clone(),
             token_contract: token_contract.clone(),
             token_id,
             price,
-            currency: currency.clone(),
+            currency,
             status: ListingStatus::Active,
             created_at: current_time,
             expires_at,
-        };
+        });
+        Self::index_listing(&env, &token_contract, price, listing_id, expires_at);
 
-        // Store the listing
-        env.storage().persistent().set(&DataKey::Listing(listing_id), &listing);
-        
-        // Update user listings
-        let mut user_listings: Vec<u64> = env.storage()
-            .persistent()
-            .get(&DataKey::UserListings(seller.clone()))
-            .unwrap_or(Vec::new(&env));
-        user_listings.push_back(listing_id);
-        env.storage().persistent().set(&DataKey::UserListings(seller.clone()), &user_listings);
+        Ok(listing_id)
+    }
 
-        // Update next listing ID
-        env.storage().instance().set(&DataKey::NextListingId, &(listing_id + 1));
+    /// Add open fixed-price listings left out of a full collection index, e.g. once sales
+    /// have freed up room. Skips listings that are missing, not active, lapsed, auctions,
+    /// or already indexed. Returns the number of listings indexed.
+    pub fn index_listings(env: Env, listing_ids: Vec<u64>) -> Result<u32, MarketplaceError> {
+        Self::require_initialized(&env)?;
 
-        // Emit event
-        env.events().publish(
-            (symbol_short!("listing"), symbol_short!("created")),
-            MarketplaceEvent::ListingCreated(
-                listing_id,
-                seller,
-                token_contract,
-                token_id,
-                price,
-            ),
-        );
+        if listing_ids.len() > MAX_BATCH_SIZE {
+            return Err(MarketplaceError::BatchTooLarge);
+        }
+
+        let current_time = env.ledger().timestamp();
+        let mut indexed = 0u32;
+        for listing_id in listing_ids.iter() {
+            let listing: Listing = match env.storage().persistent().get(&DataKey::Listing(listing_id)) {
+                Some(listing) => listing,
+                None => continue,
+            };
+            if listing.status != ListingStatus::Active
+                || current_time > listing.expires_at
+                || env.storage().persistent().has(&DataKey::Auction(listing_id))
+                || Self::get_collection_listings(&env, &listing.token_contract)
+                    .iter()
+                    .any(|(_, indexed_id, _)| indexed_id == listing_id)
+            {
+                continue;
+            }
+
+            if Self::index_listing(&env, &listing.token_contract, listing.price, listing_id, listing.expires_at) {
+                indexed += 1;
+            }
+        }
+
+        Ok(indexed)
+    }
+
+    /// Create a timed auction, escrowing the NFT in the marketplace. Bids are escrowed too and
+    /// must meet `reserve_price`.
+    pub fn create_auction(
+        env: Env,
+        seller: Address,
+        token_contract: Address,
+        token_id: u64,
+        reserve_price: i128,
+        currency: Address,
+        duration: u64, // Duration in seconds
+    ) -> Result<u64, MarketplaceError> {
+        Self::require_trading(&env)?;
+        seller.require_auth();
+
+        if reserve_price <= 0 {
+            return Err(MarketplaceError::InvalidPrice);
+        }
+        if !Self::is_accepted_currency(env.clone(), currency.clone()) {
+            return Err(MarketplaceError::CurrencyNotAccepted);
+        }
+
+        let current_time = env.ledger().timestamp();
+        let listing_id = Self::store_listing(&env, Listing {
+            id: 0,
+            seller: seller.clone(),
+            token_contract,
+            token_id,
+            price: reserve_price,
+            currency,
+            status: ListingStatus::Active,
+            created_at: current_time,
+            expires_at: current_time + duration,
+        });
+        let auction = Auction { highest_bid: 0, highest_bidder: seller, has_bid: false };
+        Self::set_persistent(&env, &DataKey::Auction(listing_id), &auction);
 
         Ok(listing_id)
     }
 
-    /// Purchase a listing
-    pub fn purchase_listing(
+    /// Bid on an auction, escrowing `amount` and refunding the previous highest bidder
+    pub fn place_bid(
         env: Env,
-        buyer: Address,
+        bidder: Address,
         listing_id: u64,
+        amount: i128,
     ) -> Result<(), MarketplaceError> {
-        Self::require_initialized(&env)?;
-        buyer.require_auth();
+        Self::require_trading(&env)?;
+        bidder.require_auth();
 
-        let mut listing: Listing = env.storage()
+        let listing: Listing = env.storage()
             .persistent()
             .get(&DataKey::Listing(listing_id))
             .ok_or(MarketplaceError::ListingNotFound)?;
+        let mut auction: Auction = env.storage()
+            .persistent()
+            .get(&DataKey::Auction(listing_id))
+            .ok_or(MarketplaceError::NotAuction)?;
 
         if listing.status != ListingStatus::Active {
             return Err(MarketplaceError::ListingNotActive);
         }
-
-        let current_time = env.ledger().timestamp();
-        if current_time > listing.expires_at {
-            listing.status = ListingStatus::Expired;
-            env.storage().persistent().set(&DataKey::Listing(listing_id), &listing);
+        if env.ledger().timestamp() > listing.expires_at {
             return Err(MarketplaceError::ListingExpired);
         }
+        if bidder == listing.seller {
+            return Err(MarketplaceError::SelfTrade);
+        }
+        if amount < listing.price || amount <= auction.highest_bid {
+            return Err(MarketplaceError::BidTooLow);
+        }
 
-        // Calculate fees
-        let marketplace_fee: u32 = env.storage().instance().get(&DataKey::MarketplaceFee).unwrap_or(250); // 2.5% default
-        let fee_amount = (listing.price * marketplace_fee as i128) / 10000;
-        let seller_amount = listing.price - fee_amount;
-
-        // Transfer payment to seller
         let currency_client = token::Client::new(&env, &listing.currency);
-        currency_client.transfer(&buyer, &listing.seller, &seller_amount);
+        let marketplace = env.current_contract_address();
+        currency_client.transfer(&bidder, &marketplace, &amount);
+        Self::add_escrow(&env, &listing.currency, amount);
 
-        // Transfer fee to treasury
-        if fee_amount > 0 {
-            let treasury: Address = env.storage().instance().get(&DataKey::TreasuryAddress).unwrap();
-            currency_client.transfer(&buyer, &treasury, &fee_amount);
+        // A failed refund must not block new bids, so it is owed to the bidder instead
+        if auction.has_bid {
+            Self::pay_or_owe(&env, &listing.currency, &auction.highest_bidder, auction.highest_bid);
         }
 
-        // Transfer NFT to buyer
-        let token_client = token::Client::new(&env, &listing.token_contract);
-        token_client.transfer(&listing.seller, &buyer, &(listing.token_id as i128));
+        auction.highest_bid = amount;
+        auction.highest_bidder = bidder.clone();
+        auction.has_bid = true;
+        Self::set_persistent(&env, &DataKey::Auction(listing_id), &auction);
 
-        // Update listing status
-        listing.status = ListingStatus::Sold;
-        env.storage().persistent().set(&DataKey::Listing(listing_id), &listing);
-
-        // Emit event
         env.events().publish(
-            (symbol_short!("listing"), symbol_short!("purchased")),
-            MarketplaceEvent::ListingPurchased(
-                listing_id,
-                listing.seller.clone(),
-                buyer.clone(),
-                listing.price,
-            ),
+            (symbol_short!("auction"), symbol_short!("bid")),
+            MarketplaceEvent::BidPlaced(listing_id, bidder, amount),
         );
 
         Ok(())
     }
 
-    /// Cancel a listing
-    pub fn cancel_listing(
-        env: Env,
-        seller: Address,
-        listing_id: u64,
-    ) -> Result<(), MarketplaceError> {
+    /// Settle an auction after it ends: the winner receives the escrowed NFT and the seller the
+    /// winning bid minus the fee. An auction without bids expires and the NFT goes back to the
+    /// seller. Anyone may call this.
+    pub fn settle_auction(env: Env, listing_id: u64) -> Result<(), MarketplaceError> {
         Self::require_initialized(&env)?;
-        seller.require_auth();
 
-        let mut listing: Listing = env.storage()
+        let listing: Listing = env.storage()
             .persistent()
             .get(&DataKey::Listing(listing_id))
             .ok_or(MarketplaceError::ListingNotFound)?;
+        let auction: Auction = env.storage()
+            .persistent()
+            .get(&DataKey::Auction(listing_id))
+            .ok_or(MarketplaceError::NotAuction)?;
 
-        if listing.seller != seller {
-            return Err(MarketplaceError::NotAuthorized);
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+        if env.ledger().timestamp() <= listing.expires_at {
+            return Err(MarketplaceError::AuctionNotEnded);
+        }
+
+        Self::settle(&env, listing, auction);
+        Ok(())
+    }
+
+    /// Settle every ended auction in `listing_ids` (at most `MAX_BATCH_SIZE`), skipping ones
+    /// that are missing, not auctions, not active, or still running. Returns the number settled.
+    pub fn settle_auctions(env: Env, listing_ids: Vec<u64>) -> Result<u32, MarketplaceError> {
+        Self::require_initialized(&env)?;
+
+        if listing_ids.len() > MAX_BATCH_SIZE {
+            return Err(MarketplaceError::BatchTooLarge);
+        }
+
+        let current_time = env.ledger().timestamp();
+        let mut settled = 0u32;
+        for listing_id in listing_ids.iter() {
+            let listing: Listing = match env.storage().persistent().get(&DataKey::Listing(listing_id)) {
+                Some(listing) => listing,
+                None => continue,
+            };
+            let auction: Auction = match env.storage().persistent().get(&DataKey::Auction(listing_id)) {
+                Some(auction) => auction,
+                None => continue,
+            };
+            if listing.status != ListingStatus::Active || current_time <= listing.expires_at {
+                continue;
+            }
+
+            Self::settle(&env, listing, auction);
+            settled += 1;
         }
 
+        Ok(settled)
+    }
+
+    /// Take back the winning bid of an auction nobody settled within `SETTLEMENT_GRACE_PERIOD`
+    /// of its end (highest bidder only). The auction is cancelled and the NFT returned to the seller.
+    pub fn reclaim_bid(env: Env, listing_id: u64) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+
+        let mut listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
+        let auction: Auction = env.storage()
+            .persistent()
+            .get(&DataKey::Auction(listing_id))
+            .ok_or(MarketplaceError::NotAuction)?;
+
         if listing.status != ListingStatus::Active {
             return Err(MarketplaceError::ListingNotActive);
         }
+        if !auction.has_bid {
+            return Err(MarketplaceError::NotAuthorized);
+        }
+        auction.highest_bidder.require_auth();
+        if env.ledger().timestamp() <= listing.expires_at + SETTLEMENT_GRACE_PERIOD {
+            return Err(MarketplaceError::AuctionNotEnded);
+        }
 
         listing.status = ListingStatus::Cancelled;
-        env.storage().persistent().set(&DataKey::Listing(listing_id), &listing);
+        Self::set_persistent(&env, &DataKey::Listing(listing_id), &listing);
+        Self::close_listing(&env, &listing);
+
+        Self::pay_or_owe(&env, &listing.currency, &auction.highest_bidder, auction.highest_bid);
+        // A collection that refuses the transfer must not keep the bid locked as well
+        let _ = AccessControlNFTClient::new(&env, &listing.token_contract).try_transfer(
+            &env.current_contract_address(),
+            &listing.seller,
+            &listing.token_id,
+        );
 
-        // Emit event
         env.events().publish(
-            (symbol_short!("listing"), symbol_short!("cancelled")),
-            MarketplaceEvent::ListingCancelled(
-                listing_id,
-                seller,
-            ),
+            (symbol_short!("auction"), symbol_short!("reclaimed")),
+            MarketplaceEvent::BidReclaimed(listing_id, auction.highest_bidder, auction.highest_bid),
         );
 
         Ok(())
     }
 
-    /// Get a listing by ID
-    pub fn get_listing(env: Env, listing_id: u64) -> Option<Listing> {
-        env.storage().persistent().get(&DataKey::Listing(listing_id))
+    /// Get the bidding state of an auction listing
+    pub fn get_auction(env: Env, listing_id: u64) -> Option<Auction> {
+        env.storage().persistent().get(&DataKey::Auction(listing_id))
     }
 
-    /// Get user listings
-    pub fn get_user_listings(env: Env, user: Address) -> Vec<u64> {
+    /// Get the escrowed funds owed to an account whose refund or payout transfer failed
+    pub fn pending_refund(env: Env, account: Address, currency: Address) -> i128 {
         env.storage()
             .persistent()
-            .get(&DataKey::UserListings(user))
-            .unwrap_or(Vec::new(&env))
+            .get(&DataKey::PendingRefund(account, currency))
+            .unwrap_or(0)
     }
 
-    /// Update marketplace fee (owner only)
-    pub fn update_marketplace_fee(
-        env: Env,
-        new_fee: u32,
-    ) -> Result<(), MarketplaceError> {
-        Self::require_initialized(&env)?;
-        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        owner.require_auth();
+    /// Withdraw owed refunds or payouts. Returns the amount withdrawn.
+    pub fn withdraw_refund(env: Env, account: Address, currency: Address) -> Result<i128, MarketplaceError> {
+        account.require_auth();
 
-        env.storage().instance().set(&DataKey::MarketplaceFee, &new_fee);
-        Ok(())
+        let key = DataKey::PendingRefund(account.clone(), currency.clone());
+        let owed: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if owed > 0 {
+            env.storage().persistent().remove(&key);
+            Self::add_escrow(&env, &currency, -owed);
+            token::Client::new(&env, &currency).transfer(&env.current_contract_address(), &account, &owed);
+        }
+        Ok(owed)
     }
 
-    /// Update treasury address (owner only)
-    pub fn update_treasury(
+    /// Offer to buy a listing for `amount`, escrowed in the marketplace until accepted or cancelled.
+    /// Offers must be at least `MIN_OFFER_BPS` of the price, and a listing takes at most
+    /// `MAX_OFFERS_PER_LISTING` of them.
+    pub fn make_offer(
         env: Env,
-        new_treasury: Address,
+        buyer: Address,
+        listing_id: u64,
+        amount: i128,
+        expires_at: u64,
     ) -> Result<(), MarketplaceError> {
-        Self::require_initialized(&env)?;
-        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        owner.require_auth();
+        Self::require_trading(&env)?;
+        buyer.require_auth();
 
-        env.storage().instance().set(&DataKey::TreasuryAddress, &new_treasury);
-        Ok(())
-    }
+        let listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
 
-    /// Transfer ownership
-    pub fn transfer_ownership(
-        env: Env,
-        new_owner: Address,
-    ) -> Result<(), MarketplaceError> {
-        Self::require_initialized(&env)?;
-        let current_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        current_owner.require_auth();
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+        if env.ledger().timestamp() > listing.expires_at {
+            return Err(MarketplaceError::ListingExpired);
+        }
+        if buyer == listing.seller {
+            return Err(MarketplaceError::SelfTrade);
+        }
+        if env.storage().persistent().has(&DataKey::Auction(listing_id)) {
+            return Err(MarketplaceError::AuctionListing);
+        }
+        if amount <= 0 {
+            return Err(MarketplaceError::InvalidPrice);
+        }
+        let min_offer = listing.price.checked_mul(MIN_OFFER_BPS).ok_or(MarketplaceError::ArithmeticOverflow)? / 10000;
+        if amount < min_offer {
+            return Err(MarketplaceError::OfferTooLow);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(MarketplaceError::OfferExpired);
+        }
+        if env.storage().persistent().has(&DataKey::Offer(listing_id, buyer.clone())) {
+            return Err(MarketplaceError::OfferExists);
+        }
+        let mut buyers = Self::get_offer_buyers(&env, listing_id);
+        if buyers.len() >= MAX_OFFERS_PER_LISTING {
+            return Err(MarketplaceError::TooManyOffers);
+        }
 
-        env.storage().instance().set(&DataKey::Owner, &new_owner);
+        token::Client::new(&env, &listing.currency).transfer(&buyer, &env.current_contract_address(), &amount);
+        Self::add_escrow(&env, &listing.currency, amount);
+
+        Self::set_persistent(&env, &DataKey::Offer(listing_id, buyer.clone()), &Offer { amount, expires_at });
+        buyers.push_back(buyer.clone());
+        Self::set_persistent(&env, &DataKey::OfferBuyers(listing_id), &buyers);
 
-        // Emit event
         env.events().publish(
-            (symbol_short!("owner"), symbol_short!("transfer")),
-            MarketplaceEvent::OwnershipTransferred(
-                current_owner,
-                new_owner,
-            ),
+            (symbol_short!("offer"), symbol_short!("made")),
+            MarketplaceEvent::OfferMade(listing_id, buyer, amount),
         );
 
         Ok(())
     }
 
-    /// Get marketplace fee
-    pub fn get_marketplace_fee(env: Env) -> u32 {
-        env.storage().instance().get(&DataKey::MarketplaceFee).unwrap_or(250)
-    }
+    /// Accept a buyer's offer (seller only), completing the sale with the escrowed funds
+    /// and refunding every other outstanding offer on the listing
+    pub fn accept_offer(
+        env: Env,
+        seller: Address,
+        listing_id: u64,
+        buyer: Address,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_trading(&env)?;
+        seller.require_auth();
 
-    /// Get treasury address
-    pub fn get_treasury(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::TreasuryAddress)
-    }
+        let listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
 
-    /// Get owner address
-    pub fn get_owner(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::Owner)
+        if listing.seller != seller {
+            return Err(MarketplaceError::NotAuthorized);
+        }
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+
+        let offer: Offer = env.storage()
+            .persistent()
+            .get(&DataKey::Offer(listing_id, buyer.clone()))
+            .ok_or(MarketplaceError::OfferNotFound)?;
+        if env.ledger().timestamp() > offer.expires_at {
+            return Err(MarketplaceError::OfferExpired);
+        }
+
+        Self::remove_offer(&env, listing_id, &buyer);
+        Self::settle_escrowed_sale(&env, listing.clone(), &buyer, offer.amount);
+
+        // Return the escrow of every losing offer
+        for other in Self::get_offer_buyers(&env, listing_id).iter() {
+            Self::refund_offer(&env, &listing, &other);
+        }
+
+        env.events().publish(
+            (symbol_short!("offer"), symbol_short!("accepted")),
+            MarketplaceEvent::OfferAccepted(listing_id, buyer, offer.amount),
+        );
+
+        Ok(())
     }
 
-    /// Check if marketplace is initialized
-    pub fn is_initialized(env: Env) -> bool {
-        env.storage().instance().get(&DataKey::Initialized).unwrap_or(false)
+    /// Withdraw an offer and get the escrowed funds back
+    pub fn cancel_offer(
+        env: Env,
+        buyer: Address,
+        listing_id: u64,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        buyer.require_auth();
+
+        let listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
+        if !env.storage().persistent().has(&DataKey::Offer(listing_id, buyer.clone())) {
+            return Err(MarketplaceError::OfferNotFound);
+        }
+
+        Self::refund_offer(&env, &listing, &buyer);
+
+        env.events().publish(
+            (symbol_short!("offer"), symbol_short!("cancelled")),
+            MarketplaceEvent::OfferCancelled(listing_id, buyer),
+        );
+
+        Ok(())
     }
 
-    /// Private helper to check if marketplace is initialized
-    fn require_initialized(env: &Env) -> Result<(), MarketplaceError> {
-        if !env.storage().instance().get(&DataKey::Initialized).unwrap_or(false) {
-            return Err(MarketplaceError::NotInitialized);
+    /// Get a buyer's outstanding offer on a listing
+    pub fn get_offer(env: Env, listing_id: u64, buyer: Address) -> Option<Offer> {
+        env.storage().persistent().get(&DataKey::Offer(listing_id, buyer))
+    }
+
+    /// Purchase a listing (the buyer must approve the marketplace for the price). The NFT is
+    /// delivered from the marketplace's escrow.
+    /// With `redeem_points` set, the buyer's loyalty points are spent to rebate the fee.
+    pub fn purchase_listing(
+        env: Env,
+        buyer: Address,
+        listing_id: u64,
+        redeem_points: bool,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_trading(&env)?;
+        buyer.require_auth();
+
+        Self::execute_purchase(&env, &buyer, listing_id, redeem_points)
+    }
+
+    /// Buy up to `count` (capped at `MAX_BATCH_SIZE`) of the cheapest active listings for a
+    /// collection without their combined price exceeding `max_total`. Returns the purchased listing IDs.
+    pub fn sweep_collection(
+        env: Env,
+        buyer: Address,
+        token_contract: Address,
+        count: u32,
+        max_total: i128,
+    ) -> Result<Vec<u64>, MarketplaceError> {
+        Self::require_trading(&env)?;
+        buyer.require_auth();
+
+        // Walk a snapshot of the collection index, which is already sorted cheapest first;
+        // each purchase removes its entry from the stored index
+        let count = count.min(MAX_BATCH_SIZE);
+        let current_time = env.ledger().timestamp();
+        let mut purchased: Vec<u64> = Vec::new(&env);
+        let mut total = 0i128;
+        for (price, listing_id, expires_at) in Self::get_collection_listings(&env, &token_contract).iter() {
+            if purchased.len() >= count {
+                break;
+            }
+            if current_time > expires_at {
+                continue;
+            }
+            if total + price > max_total {
+                break;
+            }
+
+            Self::execute_purchase(&env, &buyer, listing_id, false)?;
+            total += price;
+            purchased.push_back(listing_id);
+        }
+
+        Ok(purchased)
+    }
+
+    /// Cancel a listing, returning the escrowed NFT to the seller
+    pub fn cancel_listing(
+        env: Env,
+        seller: Address,
+        listing_id: u64,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        seller.require_auth();
+
+        let mut listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
+
+        if listing.seller != seller {
+            return Err(MarketplaceError::NotAuthorized);
+        }
+
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+
+        if Self::has_bids(&env, listing_id) {
+            return Err(MarketplaceError::AuctionHasBids);
         }
+
+        listing.status = ListingStatus::Cancelled;
+        Self::set_persistent(&env, &DataKey::Listing(listing_id), &listing);
+        Self::close_listing(&env, &listing);
+        Self::release_nft(&env, &listing, &seller);
+
+        // Emit event
+        env.events().publish(
+            (symbol_short!("listing"), symbol_short!("cancelled")),
+            MarketplaceEvent::ListingCancelled(
+                listing_id,
+                seller,
+            ),
+        );
+
         Ok(())
     }
-}
 
+    /// Cancel several listings in one call, skipping ones not owned by the seller or not active.
+    /// Returns the number of listings cancelled.
+    pub fn cancel_listings(
+        env: Env,
+        seller: Address,
+        listing_ids: Vec<u64>,
+    ) -> Result<u32, MarketplaceError> {
+        Self::require_initialized(&env)?;
+        seller.require_auth();
+
+        if listing_ids.len() > MAX_BATCH_SIZE {
+            return Err(MarketplaceError::BatchTooLarge);
+        }
+
+        let mut cancelled = 0u32;
+        for listing_id in listing_ids.iter() {
+            let mut listing: Listing = match env.storage().persistent().get(&DataKey::Listing(listing_id)) {
+                Some(listing) => listing,
+                None => continue,
+            };
+
+            if listing.seller != seller || listing.status != ListingStatus::Active || Self::has_bids(&env, listing_id) {
+                continue;
+            }
+
+            listing.status = ListingStatus::Cancelled;
+            Self::set_persistent(&env, &DataKey::Listing(listing_id), &listing);
+            Self::close_listing(&env, &listing);
+            Self::release_nft(&env, &listing, &seller);
+
+            env.events().publish(
+                (symbol_short!("listing"), symbol_short!("cancelled")),
+                MarketplaceEvent::ListingCancelled(
+                    listing_id,
+                    seller.clone(),
+                ),
+            );
+            cancelled += 1;
+        }
+
+        Ok(cancelled)
+    }
+
+    /// Mark listings whose expiry has passed as expired, skipping ones that are missing,
+    /// not active, or not yet expired. Returns the number of listings expired.
+    pub fn expire_listings(
+        env: Env,
+        listing_ids: Vec<u64>,
+    ) -> Result<u32, MarketplaceError> {
+        Self::require_initialized(&env)?;
+
+        if listing_ids.len() > MAX_BATCH_SIZE {
+            return Err(MarketplaceError::BatchTooLarge);
+        }
+
+        let current_time = env.ledger().timestamp();
+        let mut expired = 0u32;
+        for listing_id in listing_ids.iter() {
+            let listing: Listing = match env.storage().persistent().get(&DataKey::Listing(listing_id)) {
+                Some(listing) => listing,
+                None => continue,
+            };
+
+            // Auctions are closed through settle_auction so escrowed bids are paid out
+            if listing.status != ListingStatus::Active
+                || current_time <= listing.expires_at
+                || env.storage().persistent().has(&DataKey::Auction(listing_id))
+            {
+                continue;
+            }
+
+            Self::expire_listing(&env, listing);
+            expired += 1;
+        }
+
+        Ok(expired)
+    }
+
+    /// Return the escrowed NFT of a lapsed listing to its seller, marking the listing expired.
+    /// Reclaiming a listing that has already expired is a no-op.
+    pub fn reclaim_expired(env: Env, seller: Address, listing_id: u64) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        seller.require_auth();
+
+        let listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
+
+        if listing.seller != seller {
+            return Err(MarketplaceError::NotAuthorized);
+        }
+        if listing.status == ListingStatus::Expired {
+            return Ok(());
+        }
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+        if env.storage().persistent().has(&DataKey::Auction(listing_id)) {
+            return Err(MarketplaceError::AuctionListing);
+        }
+        if env.ledger().timestamp() <= listing.expires_at {
+            return Err(MarketplaceError::ListingNotExpired);
+        }
+
+        Self::expire_listing(&env, listing);
+        Ok(())
+    }
+
+    /// Get a listing by ID
+    pub fn get_listing(env: Env, listing_id: u64) -> Option<Listing> {
+        env.storage().persistent().get(&DataKey::Listing(listing_id))
+    }
+
+    /// Pay `royalty.bps` of the listing's sale price to `royalty.recipient` (seller only,
+    /// while the listing is active). The fee and royalty together can't exceed the price.
+    /// The royalty is locked once the listing has a bid or an offer.
+    pub fn set_royalty(
+        env: Env,
+        listing_id: u64,
+        royalty: Royalty,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_trading(&env)?;
+
+        let listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
+        listing.seller.require_auth();
+
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+        if Self::has_bids(&env, listing_id) || !Self::get_offer_buyers(&env, listing_id).is_empty() {
+            return Err(MarketplaceError::RoyaltyLocked);
+        }
+        if Self::get_seller_fee(env.clone(), listing.seller, listing.currency) + royalty.bps > 10000 {
+            return Err(MarketplaceError::InvalidRoyalty);
+        }
+
+        Self::set_persistent(&env, &DataKey::Royalty(listing_id), &royalty);
+        Ok(())
+    }
+
+    /// Get the creator royalty paid on a listing's sale, if any
+    pub fn get_royalty(env: Env, listing_id: u64) -> Option<Royalty> {
+        env.storage().persistent().get(&DataKey::Royalty(listing_id))
+    }
+
+    /// Extend the TTL of a listing, its auction, royalty and offers, and its seller's listing
+    /// index so they are not archived. Anyone may call this, e.g. a bidder keeping a
+    /// long-running auction alive.
+    pub fn bump_listing_ttl(env: Env, listing_id: u64) -> Result<(), MarketplaceError> {
+        let listing = Self::get_listing(env.clone(), listing_id)
+            .ok_or(MarketplaceError::ListingNotFound)?;
+
+        let mut keys = Vec::from_array(&env, [
+            DataKey::Listing(listing_id),
+            DataKey::UserListings(listing.seller),
+            DataKey::CollectionListings(listing.token_contract),
+            DataKey::Auction(listing_id),
+            DataKey::Royalty(listing_id),
+            DataKey::Payees(listing_id),
+            DataKey::OfferBuyers(listing_id),
+        ]);
+        for buyer in Self::get_offer_buyers(&env, listing_id).iter() {
+            keys.push_back(DataKey::Offer(listing_id, buyer));
+        }
+
+        let persistent = env.storage().persistent();
+        for key in keys.iter() {
+            if persistent.has(&key) {
+                persistent.extend_ttl(&key, LISTING_TTL_THRESHOLD, LISTING_TTL_EXTEND);
+            }
+        }
+        env.storage().instance().extend_ttl(LISTING_TTL_THRESHOLD, LISTING_TTL_EXTEND);
+        Ok(())
+    }
+
+    /// Purchasable listings with IDs from `start` onward, at most `limit` (capped at
+    /// `MAX_PAGE_SIZE`) IDs scanned. Also returns the ID to pass as `start` for the next page.
+    pub fn get_active_listings(env: Env, start: u64, limit: u32) -> (Vec<Listing>, u64) {
+        let mut active: Vec<Listing> = Vec::new(&env);
+        let last_id = env.storage().instance().get(&DataKey::NextListingId).unwrap_or(1u64) - 1;
+        let start = start.max(1);
+        if start > last_id {
+            return (active, start);
+        }
+
+        let current_time = env.ledger().timestamp();
+        let end = last_id.min(start + limit.min(MAX_PAGE_SIZE) as u64 - 1);
+        for listing_id in start..=end {
+            let listing: Option<Listing> = env.storage().persistent().get(&DataKey::Listing(listing_id));
+            if let Some(listing) = listing {
+                if listing.status == ListingStatus::Active && current_time <= listing.expires_at {
+                    active.push_back(listing);
+                }
+            }
+        }
+        (active, end + 1)
+    }
+
+    /// Get user listings
+    pub fn get_user_listings(env: Env, user: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UserListings(user))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get a user's listings that are active and not yet past their expiry
+    pub fn get_active_user_listings(env: Env, user: Address) -> Vec<u64> {
+        let current_time = env.ledger().timestamp();
+        let mut active = Vec::new(&env);
+        for listing_id in Self::get_user_listings(env.clone(), user).iter() {
+            let listing: Option<Listing> = env.storage().persistent().get(&DataKey::Listing(listing_id));
+            if let Some(listing) = listing {
+                if listing.status == ListingStatus::Active && current_time <= listing.expires_at {
+                    active.push_back(listing_id);
+                }
+            }
+        }
+        active
+    }
+
+    /// Update marketplace fee (owner only)
+    pub fn update_marketplace_fee(
+        env: Env,
+        new_fee: u32,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        if new_fee > 10000 {
+            return Err(MarketplaceError::InvalidFee);
+        }
+        env.storage().instance().set(&DataKey::MarketplaceFee, &new_fee);
+        Ok(())
+    }
+
+    /// Update treasury address (owner only)
+    pub fn update_treasury(
+        env: Env,
+        new_treasury: Address,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        env.storage().instance().set(&DataKey::TreasuryAddress, &new_treasury);
+        Ok(())
+    }
+
+    /// Add a currency listings may be priced in (owner only)
+    pub fn add_currency(
+        env: Env,
+        token: Address,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        let key = DataKey::AcceptedCurrency(token);
+        if !env.storage().instance().has(&key) {
+            env.storage().instance().set(&key, &true);
+            let count: u32 = env.storage().instance().get(&DataKey::AcceptedCurrencyCount).unwrap_or(0);
+            env.storage().instance().set(&DataKey::AcceptedCurrencyCount, &(count + 1));
+        }
+        Ok(())
+    }
+
+    /// Remove a currency from the allowlist (owner only). Existing listings are unaffected.
+    pub fn remove_currency(
+        env: Env,
+        token: Address,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        let key = DataKey::AcceptedCurrency(token);
+        if env.storage().instance().has(&key) {
+            env.storage().instance().remove(&key);
+            let count: u32 = env.storage().instance().get(&DataKey::AcceptedCurrencyCount).unwrap_or(0);
+            env.storage().instance().set(&DataKey::AcceptedCurrencyCount, &(count - 1));
+        }
+        Ok(())
+    }
+
+    /// Check if new listings may be priced in `token`; any currency is accepted until one is added
+    pub fn is_accepted_currency(env: Env, token: Address) -> bool {
+        let count: u32 = env.storage().instance().get(&DataKey::AcceptedCurrencyCount).unwrap_or(0);
+        count == 0 || env.storage().instance().has(&DataKey::AcceptedCurrency(token))
+    }
+
+    /// Transfer ownership
+    pub fn transfer_ownership(
+        env: Env,
+        new_owner: Address,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let current_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        current_owner.require_auth();
+
+        env.storage().instance().set(&DataKey::Owner, &new_owner);
 
+        // Emit event
+        env.events().publish(
+            (symbol_short!("owner"), symbol_short!("transfer")),
+            MarketplaceEvent::OwnershipTransferred(
+                current_owner,
+                new_owner,
+            ),
+        );
+
+        Ok(())
+    }
+
+    /// Replace the contract's WASM with the already-uploaded `new_wasm_hash` (owner only).
+    /// Storage is kept; the new code takes effect once this invocation finishes.
+    pub fn upgrade(
+        env: Env,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        // Emit event
+        env.events().publish(
+            (symbol_short!("contract"), symbol_short!("upgrade")),
+            MarketplaceEvent::Upgraded(new_wasm_hash),
+        );
+
+        Ok(())
+    }
+
+    /// Split the listing's seller proceeds among up to `MAX_PAYEES` payees by basis-point
+    /// shares summing to 10000 (seller only, while the listing is active)
+    pub fn set_payees(
+        env: Env,
+        listing_id: u64,
+        payees: Vec<(Address, u32)>,
+    ) -> Result<(), MarketplaceError> {
+        Self::require_trading(&env)?;
+
+        let listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
+        listing.seller.require_auth();
+
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+        if payees.is_empty() || payees.len() > MAX_PAYEES {
+            return Err(MarketplaceError::InvalidPayees);
+        }
+        let mut total_bps = 0u32;
+        for (_, share) in payees.iter() {
+            total_bps = total_bps.checked_add(share).ok_or(MarketplaceError::InvalidPayees)?;
+        }
+        if total_bps != 10000 {
+            return Err(MarketplaceError::InvalidPayees);
+        }
+
+        Self::set_persistent(&env, &DataKey::Payees(listing_id), &payees);
+        Ok(())
+    }
+
+    /// Get the payees splitting a listing's seller proceeds; empty when the seller is paid directly
+    pub fn get_payees(env: Env, listing_id: u64) -> Vec<(Address, u32)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Payees(listing_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Permanently disable listing and purchasing (owner only). There is no way back.
+    pub fn shutdown(env: Env) -> Result<(), MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        env.storage().instance().set(&DataKey::Shutdown, &true);
+        Ok(())
+    }
+
+    /// Check if the marketplace has been shut down
+    pub fn is_shutdown(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Shutdown).unwrap_or(false)
+    }
+
+    /// Send the marketplace's balance of `currency` to `to` after shutdown (owner only), less
+    /// the bids, offers, and refunds still escrowed for their owners, who can keep settling,
+    /// cancelling, and withdrawing them. Returns the amount rescued.
+    pub fn rescue(env: Env, currency: Address, to: Address) -> Result<i128, MarketplaceError> {
+        Self::require_initialized(&env)?;
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+
+        if !Self::is_shutdown(env.clone()) {
+            return Err(MarketplaceError::NotShutdown);
+        }
+
+        let currency_client = token::Client::new(&env, &currency);
+        let marketplace = env.current_contract_address();
+        let amount = currency_client.balance(&marketplace) - Self::escrowed(&env, &currency);
+        if amount > 0 {
+            currency_client.transfer(&marketplace, &to, &amount);
+        }
+        Ok(amount)
+    }
+
+    /// Get marketplace fee
+    pub fn get_marketplace_fee(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::MarketplaceFee).unwrap_or(250)
+    }
+
+    /// Get the configured volume fee tiers
+    pub fn get_fee_tiers(env: Env) -> Vec<FeeTier> {
+        env.storage().instance().get(&DataKey::FeeTiers).unwrap_or(Vec::new(&env))
+    }
+
+    /// Get a seller's cumulative sales volume in `currency`
+    pub fn get_seller_volume(env: Env, seller: Address, currency: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SellerVolume(seller, currency))
+            .unwrap_or(0)
+    }
+
+    /// Get the fee in basis points a seller currently pays on sales in `currency`, based on
+    /// their volume tier in that currency
+    pub fn get_seller_fee(env: Env, seller: Address, currency: Address) -> u32 {
+        let volume = Self::get_seller_volume(env.clone(), seller, currency);
+        let mut fee = Self::get_marketplace_fee(env.clone());
+        let mut best_threshold: Option<i128> = None;
+
+        for tier in Self::get_fee_tiers(env).iter() {
+            if volume >= tier.min_volume && best_threshold.is_none_or(|t| tier.min_volume > t) {
+                best_threshold = Some(tier.min_volume);
+                fee = tier.fee_bps;
+            }
+        }
+
+        fee
+    }
+
+    /// Get the buyer loyalty program configuration
+    pub fn get_loyalty_config(env: Env) -> LoyaltyConfig {
+        env.storage().instance().get(&DataKey::LoyaltyConfig).unwrap_or(LoyaltyConfig {
+            points_per_purchase: 0,
+            rebate_bps_per_point: 0,
+        })
+    }
+
+    /// Get a buyer's unredeemed loyalty points
+    pub fn buyer_points(env: Env, buyer: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BuyerPoints(buyer))
+            .unwrap_or(0)
+    }
+
+    /// Get the lowest price among a collection's purchasable indexed fixed-price listings
+    pub fn floor_price(env: Env, token_contract: Address) -> Option<i128> {
+        let current_time = env.ledger().timestamp();
+        Self::get_collection_listings(&env, &token_contract)
+            .iter()
+            .find(|(_, _, expires_at)| current_time <= *expires_at)
+            .map(|(price, _, _)| price)
+    }
+
+    /// Get treasury address
+    pub fn get_treasury(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TreasuryAddress)
+    }
+
+    /// Get owner address
+    pub fn get_owner(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Owner)
+    }
+
+    /// Check if marketplace is initialized
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Initialized).unwrap_or(false)
+    }
+
+    /// Private helper that settles a purchase once the buyer is authorized
+    fn execute_purchase(
+        env: &Env,
+        buyer: &Address,
+        listing_id: u64,
+        redeem_points: bool,
+    ) -> Result<(), MarketplaceError> {
+        let mut listing: Listing = env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .ok_or(MarketplaceError::ListingNotFound)?;
+
+        if listing.status != ListingStatus::Active {
+            return Err(MarketplaceError::ListingNotActive);
+        }
+
+        if env.storage().persistent().has(&DataKey::Auction(listing_id)) {
+            return Err(MarketplaceError::AuctionListing);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if current_time > listing.expires_at {
+            listing.status = ListingStatus::Expired;
+            Self::set_persistent(env, &DataKey::Listing(listing_id), &listing);
+            Self::close_listing(env, &listing);
+            return Err(MarketplaceError::ListingExpired);
+        }
+
+        // Buying your own listing would only inflate your volume tier
+        if *buyer == listing.seller {
+            return Err(MarketplaceError::SelfTrade);
+        }
+
+        // Calculate fees using the seller's volume tier, then the creator royalty
+        let marketplace_fee = Self::get_seller_fee(env.clone(), listing.seller.clone(), listing.currency.clone());
+        let royalty = Self::get_royalty(env.clone(), listing_id);
+        let royalty_amount = Self::royalty_amount(&royalty, listing.price, marketplace_fee);
+        let seller_amount = listing.price - (listing.price * marketplace_fee as i128) / 10000 - royalty_amount;
+
+        // Spend loyalty points to rebate the buyer's share of the fee
+        let loyalty = Self::get_loyalty_config(env.clone());
+        let mut points = Self::buyer_points(env.clone(), buyer.clone());
+        let mut effective_fee = marketplace_fee;
+        if redeem_points && loyalty.rebate_bps_per_point > 0 {
+            let needed = marketplace_fee.div_ceil(loyalty.rebate_bps_per_point) as u64;
+            let redeemed = points.min(needed);
+            effective_fee = marketplace_fee.saturating_sub(redeemed as u32 * loyalty.rebate_bps_per_point);
+            points -= redeemed;
+        }
+        let fee_amount = (listing.price * effective_fee as i128) / 10000;
+        let total_cost = seller_amount + fee_amount + royalty_amount;
+
+        // Verify the buyer holds and has approved enough currency before moving anything
+        let currency_client = token::Client::new(env, &listing.currency);
+        let marketplace = env.current_contract_address();
+        if currency_client.balance(buyer) < total_cost
+            || currency_client.allowance(buyer, &marketplace) < total_cost
+        {
+            return Err(MarketplaceError::InsufficientFunds);
+        }
+
+        // Mark the listing sold before any external call so it can't be bought twice
+        listing.status = ListingStatus::Sold;
+        Self::set_persistent(env, &DataKey::Listing(listing_id), &listing);
+        Self::close_listing(env, &listing);
+
+        // Track seller volume for fee tiers
+        Self::add_seller_volume(env, &listing, listing.price);
+
+        // Accrue loyalty points for the buyer
+        points += loyalty.points_per_purchase;
+        Self::set_persistent(env, &DataKey::BuyerPoints(buyer.clone()), &points);
+
+        // Pay the creator royalty first
+        if let Some(royalty) = royalty {
+            if royalty_amount > 0 {
+                currency_client.transfer_from(&marketplace, buyer, &royalty.recipient, &royalty_amount);
+                Self::publish_royalty(env, listing_id, royalty.recipient, royalty_amount);
+            }
+        }
+
+        // Transfer payment to the seller, or split it across the listing's payees
+        for (payee, amount) in Self::seller_payouts(env, &listing, seller_amount).iter() {
+            if amount > 0 {
+                currency_client.transfer_from(&marketplace, buyer, &payee, &amount);
+            }
+        }
+
+        // Transfer fee to treasury
+        if fee_amount > 0 {
+            let treasury: Address = env.storage().instance().get(&DataKey::TreasuryAddress).unwrap();
+            currency_client.transfer_from(&marketplace, buyer, &treasury, &fee_amount);
+        }
+
+        // Hand the escrowed NFT to the buyer
+        Self::release_nft(env, &listing, buyer);
+
+        // Emit event
+        env.events().publish(
+            (symbol_short!("listing"), symbol_short!("purchased")),
+            MarketplaceEvent::ListingPurchased(
+                listing_id,
+                listing.seller.clone(),
+                buyer.clone(),
+                listing.price,
+            ),
+        );
+
+        Ok(())
+    }
+
+    /// Private helper that escrows the seller's NFT, assigns the next listing ID, stores and
+    /// indexes the listing, and emits the event
+    fn store_listing(env: &Env, mut listing: Listing) -> u64 {
+        let listing_id = env.storage().instance().get(&DataKey::NextListingId).unwrap_or(1u64);
+        listing.id = listing_id;
+
+        // The marketplace holds the NFT while it is listed, so a sale never needs the seller's signature
+        AccessControlNFTClient::new(env, &listing.token_contract).transfer(
+            &listing.seller,
+            &env.current_contract_address(),
+            &listing.token_id,
+        );
+
+        // Store the listing
+        Self::set_persistent(env, &DataKey::Listing(listing_id), &listing);
+        
+        // Update user listings
+        let mut user_listings: Vec<u64> = env.storage()
+            .persistent()
+            .get(&DataKey::UserListings(listing.seller.clone()))
+            .unwrap_or(Vec::new(env));
+        user_listings.push_back(listing_id);
+        Self::set_persistent(env, &DataKey::UserListings(listing.seller.clone()), &user_listings);
+
+        // Update next listing ID
+        env.storage().instance().set(&DataKey::NextListingId, &(listing_id + 1));
+
+        // Emit event
+        env.events().publish(
+            (symbol_short!("listing"), symbol_short!("created")),
+            MarketplaceEvent::ListingCreated(
+                listing_id,
+                listing.seller,
+                listing.token_contract,
+                listing.token_id,
+                listing.price,
+            ),
+        );
+
+        listing_id
+    }
+
+    /// Private helper that completes a sale paid from funds escrowed in the marketplace:
+    /// the seller gets `amount` minus their tier fee and `buyer` receives the NFT. Payouts
+    /// that fail are owed through `withdraw_refund` so no recipient can block the sale.
+    fn settle_escrowed_sale(env: &Env, mut listing: Listing, buyer: &Address, amount: i128) {
+        listing.status = ListingStatus::Sold;
+        Self::set_persistent(env, &DataKey::Listing(listing.id), &listing);
+        Self::close_listing(env, &listing);
+
+        // The fee uses the tier the seller had before this sale, as in a direct purchase
+        let marketplace_fee = Self::get_seller_fee(env.clone(), listing.seller.clone(), listing.currency.clone());
+        let fee_amount = (amount * marketplace_fee as i128) / 10000;
+        Self::add_seller_volume(env, &listing, amount);
+
+        let royalty = Self::get_royalty(env.clone(), listing.id);
+        let royalty_amount = Self::royalty_amount(&royalty, amount, marketplace_fee);
+        if let Some(royalty) = royalty {
+            if royalty_amount > 0 {
+                Self::pay_or_owe(env, &listing.currency, &royalty.recipient, royalty_amount);
+                Self::publish_royalty(env, listing.id, royalty.recipient, royalty_amount);
+            }
+        }
+        for (payee, payout) in Self::seller_payouts(env, &listing, amount - fee_amount - royalty_amount).iter() {
+            Self::pay_or_owe(env, &listing.currency, &payee, payout);
+        }
+        if fee_amount > 0 {
+            let treasury: Address = env.storage().instance().get(&DataKey::TreasuryAddress).unwrap();
+            Self::pay_or_owe(env, &listing.currency, &treasury, fee_amount);
+        }
+
+        // Hand the escrowed NFT to the buyer
+        Self::release_nft(env, &listing, buyer);
+    }
+
+    /// Private helper splitting the seller's proceeds across the listing's payees, or paying
+    /// them all to the seller when none are set. The last payee absorbs rounding dust.
+    fn seller_payouts(env: &Env, listing: &Listing, seller_amount: i128) -> Vec<(Address, i128)> {
+        let payees = Self::get_payees(env.clone(), listing.id);
+        if payees.is_empty() {
+            return Vec::from_array(env, [(listing.seller.clone(), seller_amount)]);
+        }
+
+        let mut payouts = Vec::new(env);
+        let mut remaining = seller_amount;
+        for (i, (payee, share)) in payees.iter().enumerate() {
+            let amount = if i as u32 == payees.len() - 1 {
+                remaining
+            } else {
+                seller_amount * share as i128 / 10000
+            };
+            remaining -= amount;
+            payouts.push_back((payee, amount));
+        }
+        payouts
+    }
+
+    /// Private helper that adds a sale to the seller's volume in the listing's currency
+    fn add_seller_volume(env: &Env, listing: &Listing, amount: i128) {
+        let volume = Self::get_seller_volume(env.clone(), listing.seller.clone(), listing.currency.clone());
+        Self::set_persistent(env, &DataKey::SellerVolume(listing.seller.clone(), listing.currency.clone()), &(volume + amount));
+    }
+
+    /// Private helper computing the royalty owed on a sale, capped so fee plus royalty never exceed the price
+    fn royalty_amount(royalty: &Option<Royalty>, amount: i128, marketplace_fee: u32) -> i128 {
+        match royalty {
+            Some(royalty) => {
+                let royalty_bps = royalty.bps.min(10000u32.saturating_sub(marketplace_fee));
+                (amount * royalty_bps as i128) / 10000
+            }
+            None => 0,
+        }
+    }
+
+    /// Private helper that pays `amount` of escrowed `currency` to `to`, or records it as owed
+    /// when the transfer fails
+    fn pay_or_owe(env: &Env, currency: &Address, to: &Address, amount: i128) {
+        let currency_client = token::Client::new(env, currency);
+        if currency_client.try_transfer(&env.current_contract_address(), to, &amount).is_err() {
+            let key = DataKey::PendingRefund(to.clone(), currency.clone());
+            let owed: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            Self::set_persistent(env, &key, &(owed + amount));
+        } else {
+            Self::add_escrow(env, currency, -amount);
+        }
+    }
+
+    /// Private helper that reads the funds of `currency` held for bidders, offerers, and owed accounts
+    fn escrowed(env: &Env, currency: &Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Escrowed(currency.clone())).unwrap_or(0)
+    }
+
+    fn add_escrow(env: &Env, currency: &Address, amount: i128) {
+        let escrowed = Self::escrowed(env, currency) + amount;
+        Self::set_persistent(env, &DataKey::Escrowed(currency.clone()), &escrowed);
+    }
+
+    /// Private helper that settles an ended auction: the highest bid buys the NFT, and without
+    /// bids the listing expires back to the seller
+    fn settle(env: &Env, listing: Listing, auction: Auction) {
+        if !auction.has_bid {
+            // No bids were escrowed, so there is nothing to return
+            Self::expire_listing(env, listing);
+            return;
+        }
+
+        let listing_id = listing.id;
+        let winner = auction.highest_bidder;
+        Self::settle_escrowed_sale(env, listing, &winner, auction.highest_bid);
+
+        env.events().publish(
+            (symbol_short!("auction"), symbol_short!("settled")),
+            MarketplaceEvent::AuctionSettled(listing_id, winner, auction.highest_bid),
+        );
+    }
+
+    /// Private helper that closes a lapsed listing and returns its NFT to the seller
+    fn expire_listing(env: &Env, mut listing: Listing) {
+        listing.status = ListingStatus::Expired;
+        Self::set_persistent(env, &DataKey::Listing(listing.id), &listing);
+        Self::close_listing(env, &listing);
+        Self::release_nft(env, &listing, &listing.seller);
+
+        env.events().publish(
+            (symbol_short!("listing"), symbol_short!("expired")),
+            MarketplaceEvent::ListingExpired(listing.id),
+        );
+    }
+
+    /// Private helper that hands a listing's escrowed NFT to `to`
+    fn release_nft(env: &Env, listing: &Listing, to: &Address) {
+        AccessControlNFTClient::new(env, &listing.token_contract).transfer(
+            &env.current_contract_address(),
+            to,
+            &listing.token_id,
+        );
+    }
+
+    /// Private helper that emits the royalty payment event
+    fn publish_royalty(env: &Env, listing_id: u64, recipient: Address, amount: i128) {
+        env.events().publish(
+            (symbol_short!("royalty"), symbol_short!("paid")),
+            MarketplaceEvent::RoyaltyPaid(listing_id, recipient, amount),
+        );
+    }
+
+    /// Private helper listing the buyers with outstanding offers on a listing
+    fn get_offer_buyers(env: &Env, listing_id: u64) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OfferBuyers(listing_id))
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// Private helper that deletes a buyer's offer and drops them from the listing's offer index
+    fn remove_offer(env: &Env, listing_id: u64, buyer: &Address) -> Option<Offer> {
+        let offer: Option<Offer> = env.storage().persistent().get(&DataKey::Offer(listing_id, buyer.clone()));
+        env.storage().persistent().remove(&DataKey::Offer(listing_id, buyer.clone()));
+
+        let mut buyers = Self::get_offer_buyers(env, listing_id);
+        if let Some(index) = buyers.first_index_of(buyer) {
+            buyers.remove(index);
+            Self::set_persistent(env, &DataKey::OfferBuyers(listing_id), &buyers);
+        }
+        offer
+    }
+
+    /// Private helper that removes a buyer's offer and returns its escrow, owing it if the transfer fails
+    fn refund_offer(env: &Env, listing: &Listing, buyer: &Address) {
+        if let Some(offer) = Self::remove_offer(env, listing.id, buyer) {
+            Self::pay_or_owe(env, &listing.currency, buyer, offer.amount);
+        }
+    }
+
+    /// Private helper that checks whether an auction listing has escrowed bids
+    fn has_bids(env: &Env, listing_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get::<_, Auction>(&DataKey::Auction(listing_id))
+            .is_some_and(|auction| auction.has_bid)
+    }
+
+    /// Private helper that writes a listing or escrow entry to persistent storage and extends
+    /// its TTL, along with the contract instance
+    fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, LISTING_TTL_THRESHOLD, LISTING_TTL_EXTEND);
+        env.storage().instance().extend_ttl(LISTING_TTL_THRESHOLD, LISTING_TTL_EXTEND);
+    }
+
+    /// Private helper that updates the indexes once a listing is no longer active
+    fn close_listing(env: &Env, closed: &Listing) {
+        // Drop the listing from its collection's price index
+        let key = DataKey::CollectionListings(closed.token_contract.clone());
+        let mut index = Self::get_collection_listings(env, &closed.token_contract);
+        if let Some(position) = index.iter().position(|(_, listing_id, _)| listing_id == closed.id) {
+            index.remove(position as u32);
+            Self::set_persistent(env, &key, &index);
+        }
+
+        // Drop the listing from the seller's index, keeping the other entries in order
+        let key = DataKey::UserListings(closed.seller.clone());
+        let mut user_listings: Vec<u64> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        if let Some(index) = user_listings.first_index_of(closed.id) {
+            user_listings.remove(index);
+            Self::set_persistent(env, &key, &user_listings);
+        }
+    }
+
+    /// Private helper that adds a listing to its collection's price index, returning whether
+    /// it made it in. Lapsed entries are dropped first; a full index keeps its cheapest
+    /// `MAX_COLLECTION_LISTINGS` entries, so a pricier listing stays unindexed.
+    fn index_listing(env: &Env, token_contract: &Address, price: i128, listing_id: u64, expires_at: u64) -> bool {
+        let current_time = env.ledger().timestamp();
+        let mut index = Self::get_collection_listings(env, token_contract);
+        let mut i = 0;
+        while i < index.len() {
+            if current_time > index.get(i).unwrap().2 {
+                index.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        // Keep the collection index sorted by price; equal prices stay in listing order
+        let mut position = index.len();
+        for (i, (indexed_price, _, _)) in index.iter().enumerate() {
+            if price < indexed_price {
+                position = i as u32;
+                break;
+            }
+        }
+        let indexed = position < MAX_COLLECTION_LISTINGS;
+        if indexed {
+            index.insert(position, (price, listing_id, expires_at));
+            if index.len() > MAX_COLLECTION_LISTINGS {
+                index.pop_back();
+            }
+        }
+        Self::set_persistent(env, &DataKey::CollectionListings(token_contract.clone()), &index);
+        indexed
+    }
+
+    /// Private helper that reads a collection's indexed fixed-price listings, cheapest first
+    fn get_collection_listings(env: &Env, token_contract: &Address) -> Vec<(i128, u64, u64)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CollectionListings(token_contract.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// Private helper that validates and writes the reconfigurable marketplace settings
+    fn store_config(
+        env: &Env,
+        treasury: Address,
+        marketplace_fee: u32,
+        fee_tiers: Vec<FeeTier>,
+        loyalty: LoyaltyConfig,
+    ) -> Result<(), MarketplaceError> {
+        if marketplace_fee > 10000 || fee_tiers.iter().any(|tier| tier.fee_bps > 10000) {
+            return Err(MarketplaceError::InvalidFee);
+        }
+
+        env.storage().instance().set(&DataKey::TreasuryAddress, &treasury);
+        env.storage().instance().set(&DataKey::MarketplaceFee, &marketplace_fee);
+        env.storage().instance().set(&DataKey::FeeTiers, &fee_tiers);
+        env.storage().instance().set(&DataKey::LoyaltyConfig, &loyalty);
+        Ok(())
+    }
+
+    /// Private helper to check the marketplace is initialized and not shut down
+    fn require_trading(env: &Env) -> Result<(), MarketplaceError> {
+        Self::require_initialized(env)?;
+        if Self::is_shutdown(env.clone()) {
+            return Err(MarketplaceError::ContractShutdown);
+        }
+        Ok(())
+    }
+
+    /// Private helper to check if marketplace is initialized
+    fn require_initialized(env: &Env) -> Result<(), MarketplaceError> {
+        if !env.storage().instance().get(&DataKey::Initialized).unwrap_or(false) {
+            return Err(MarketplaceError::NotInitialized);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_control_nft::AccessControlNFT;
+    use crate::blocklist_token::{BlocklistToken, BlocklistTokenClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+        token, xdr, Address, Bytes, Env, IntoVal, String, Symbol, Vec,
+    };
+
+    /// Currency stub whose `transfer_from` tries to buy the same listing again and records
+    /// whether the host refused the nested call
+    #[contract]
+    pub struct ReentrantCurrency;
+
+    #[contractimpl]
+    impl ReentrantCurrency {
+        pub fn balance(_env: Env, _id: Address) -> i128 {
+            i128::MAX
+        }
+
+        pub fn allowance(_env: Env, _from: Address, _spender: Address) -> i128 {
+            i128::MAX
+        }
+
+        pub fn transfer_from(env: Env, spender: Address, from: Address, _to: Address, _amount: i128) {
+            if env.storage().instance().has(&symbol_short!("reentered")) {
+                return;
+            }
+            let listing_id: u64 = env.storage().instance().get(&symbol_short!("listing")).unwrap();
+            let result = AdvancedMarketplaceClient::new(&env, &spender).try_purchase_listing(&from, &listing_id, &false);
+            env.storage().instance().set(&symbol_short!("reentered"), &matches!(result, Err(Err(_))));
+        }
+
+        pub fn set_listing(env: Env, listing_id: u64) {
+            env.storage().instance().set(&symbol_short!("listing"), &listing_id);
+        }
+
+        pub fn reentry_rejected_by_host(env: Env) -> bool {
+            env.storage().instance().get(&symbol_short!("reentered")).unwrap_or(false)
+        }
+    }
+
+    fn setup(env: &Env) -> AdvancedMarketplaceClient<'_> {
+        setup_with_tiers(env, Vec::new(env))
+    }
+
+    fn setup_with_tiers(env: &Env, fee_tiers: Vec<FeeTier>) -> AdvancedMarketplaceClient<'_> {
+        let loyalty = LoyaltyConfig { points_per_purchase: 0, rebate_bps_per_point: 0 };
+        setup_with_config(env, fee_tiers, loyalty)
+    }
+
+    fn setup_with_config(env: &Env, fee_tiers: Vec<FeeTier>, loyalty: LoyaltyConfig) -> AdvancedMarketplaceClient<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AdvancedMarketplace);
+        let client = AdvancedMarketplaceClient::new(env, &contract_id);
+
+        client.initialize(&Address::generate(env), &Address::generate(env), &250, &fee_tiers, &loyalty);
+        client
+    }
+
+    fn create_token(env: &Env) -> Address {
+        env.register_stellar_asset_contract(Address::generate(env))
+    }
+
+    /// Registers an AccessControlNFT collection and mints tokens 1..=count to `owner`
+    fn create_nft(env: &Env, owner: &Address, count: u32) -> Address {
+        let nft = env.register_contract(None, AccessControlNFT);
+        let nft_client = AccessControlNFTClient::new(env, &nft);
+        let admin = Address::generate(env);
+        nft_client.initialize(
+            &admin,
+            &Symbol::new(env, "Collection"),
+            &Symbol::new(env, "COL"),
+            &Vec::from_array(env, [admin.clone()]),
+            &1,
+        );
+        for _ in 0..count {
+            nft_client.mint(&admin, owner, &Bytes::new(env));
+        }
+        nft
+    }
+
+    /// Registers a BlocklistToken, whose transfers to blocked accounts fail, as a currency
+    fn create_blocklist_currency(env: &Env) -> (Address, BlocklistTokenClient<'_>) {
+        let currency = env.register_contract(None, BlocklistToken);
+        let currency_client = BlocklistTokenClient::new(env, &currency);
+        currency_client.initialize(
+            &Address::generate(env),
+            &String::from_str(env, "Blocklist"),
+            &String::from_str(env, "BLK"),
+            &7,
+            &0,
+        );
+        (currency, currency_client)
+    }
+
+    fn mint(env: &Env, token: &Address, to: &Address, amount: i128) {
+        token::StellarAssetClient::new(env, token).mint(to, &amount);
+    }
+
+    fn approve(env: &Env, client: &AdvancedMarketplaceClient, token: &Address, owner: &Address, amount: i128) {
+        token::Client::new(env, token).approve(owner, &client.address, &amount, &1000);
+    }
+
+    /// Lists `token_id` from a fresh collection minted to `seller`
+    fn list(env: &Env, client: &AdvancedMarketplaceClient, seller: &Address, token_id: u64) -> u64 {
+        client.create_listing(
+            seller,
+            &create_nft(env, seller, token_id as u32),
+            &token_id,
+            &1000,
+            &Address::generate(env),
+            &3600,
+        )
+    }
+
+    #[test]
+    fn test_reinitialize_before_lock() {
+        let env = Env::default();
+        let client = setup(&env);
+        let treasury = Address::generate(&env);
+        let loyalty = LoyaltyConfig { points_per_purchase: 1, rebate_bps_per_point: 10 };
+
+        client.reinitialize(&2, &treasury, &100, &Vec::new(&env), &loyalty);
+        assert_eq!(client.init_version(), 2);
+        assert_eq!(client.get_treasury(), Some(treasury));
+        assert_eq!(client.get_marketplace_fee(), 100);
+        assert_eq!(client.get_loyalty_config(), loyalty);
+    }
+
+    #[test]
+    fn test_reinitialize_after_lock_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let loyalty = LoyaltyConfig { points_per_purchase: 0, rebate_bps_per_point: 0 };
+
+        client.lock_initialization();
+        let result = client.try_reinitialize(&2, &Address::generate(&env), &100, &Vec::new(&env), &loyalty);
+        assert_eq!(result, Err(Ok(MarketplaceError::InitializationLocked)));
+    }
+
+    #[test]
+    fn test_shutdown_disables_trading_permanently() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let listing_id = list(&env, &client, &seller, 1);
+
+        client.shutdown();
+        assert!(client.is_shutdown());
+
+        let result = client.try_create_listing(&seller, &Address::generate(&env), &2, &1000, &Address::generate(&env), &3600);
+        assert_eq!(result, Err(Ok(MarketplaceError::ContractShutdown)));
+        let result = client.try_purchase_listing(&Address::generate(&env), &listing_id, &false);
+        assert_eq!(result, Err(Ok(MarketplaceError::ContractShutdown)));
+        let result = client.try_sweep_collection(&Address::generate(&env), &Address::generate(&env), &1, &1000);
+        assert_eq!(result, Err(Ok(MarketplaceError::ContractShutdown)));
+    }
+
+    #[test]
+    fn test_rescue_after_shutdown() {
+        let env = Env::default();
+        let client = setup(&env);
+        let currency = create_token(&env);
+        let recovery = Address::generate(&env);
+        mint(&env, &currency, &client.address, 500);
+
+        let result = client.try_rescue(&currency, &recovery);
+        assert_eq!(result, Err(Ok(MarketplaceError::NotShutdown)));
+
+        client.shutdown();
+        assert_eq!(client.rescue(&currency, &recovery), 500);
+        assert_eq!(token::Client::new(&env, &currency).balance(&recovery), 500);
+        assert_eq!(token::Client::new(&env, &currency).balance(&client.address), 0);
+    }
+
+    #[test]
+    fn test_rescue_leaves_escrow_for_owners() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let (currency, currency_client) = create_blocklist_currency(&env);
+        let listing_id = client.create_auction(&seller, &create_nft(&env, &seller, 1), &1, &100, &currency, &3600);
+        let (first, second) = (Address::generate(&env), Address::generate(&env));
+        currency_client.mint(&first, &1000);
+        currency_client.mint(&second, &1000);
+        currency_client.mint(&client.address, &500);
+
+        // The outbid refund fails, so 150 is owed to the first bidder and 200 backs the live bid
+        client.place_bid(&first, &listing_id, &150);
+        currency_client.block_user(&first);
+        client.place_bid(&second, &listing_id, &200);
+        assert_eq!(client.pending_refund(&first, &currency), 150);
+
+        client.shutdown();
+        assert_eq!(client.rescue(&currency, &recovery), 500);
+        assert_eq!(currency_client.balance(&client.address), 350);
+
+        currency_client.unblock_user(&first);
+        assert_eq!(client.withdraw_refund(&first, &currency), 150);
+        assert_eq!(currency_client.balance(&first), 1000);
+
+        // The live bid still settles after shutdown, and nothing is left to rescue
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        client.settle_auction(&listing_id);
+        assert_eq!(currency_client.balance(&client.address), 0);
+        assert_eq!(client.rescue(&currency, &recovery), 0);
+    }
+
+    #[test]
+    fn test_expire_listings_batch() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+
+        let stale = list(&env, &client, &seller, 1);
+        let fresh = client.create_listing(&seller, &create_nft(&env, &seller, 2), &2, &1000, &Address::generate(&env), &7200);
+
+        // Nothing has expired yet
+        assert_eq!(client.expire_listings(&Vec::from_array(&env, [stale, fresh])), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        let expired = client.expire_listings(&Vec::from_array(&env, [stale, fresh, 99]));
+
+        assert_eq!(expired, 1);
+        assert_eq!(client.get_listing(&stale).unwrap().status, ListingStatus::Expired);
+        assert_eq!(client.get_listing(&fresh).unwrap().status, ListingStatus::Active);
+    }
+
+    #[test]
+    fn test_reclaim_expired_returns_nft() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 1);
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &Address::generate(&env), &3600);
+
+        let result = client.try_reclaim_expired(&seller, &listing_id);
+        assert_eq!(result, Err(Ok(MarketplaceError::ListingNotExpired)));
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        let result = client.try_reclaim_expired(&Address::generate(&env), &listing_id);
+        assert_eq!(result, Err(Ok(MarketplaceError::NotAuthorized)));
+
+        client.reclaim_expired(&seller, &listing_id);
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Expired);
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), seller);
+        assert_eq!(client.get_user_listings(&seller).len(), 0);
+    }
+
+    #[test]
+    fn test_double_reclaim_is_noop() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 1);
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &Address::generate(&env), &3600);
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        client.reclaim_expired(&seller, &listing_id);
+
+        // The NFT has already left escrow, so a second reclaim does nothing
+        let nft_client = AccessControlNFTClient::new(&env, &nft);
+        let buyer = Address::generate(&env);
+        nft_client.transfer(&seller, &buyer, &1);
+        client.reclaim_expired(&seller, &listing_id);
+        assert_eq!(nft_client.owner_of(&1), buyer);
+    }
+
+    #[test]
+    fn test_reclaim_expired_rejects_auctions() {
+        let env = Env::default();
+        let (client, seller, _nft, _currency, listing_id) = auction_setup(&env);
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        let result = client.try_reclaim_expired(&seller, &listing_id);
+        assert_eq!(result, Err(Ok(MarketplaceError::AuctionListing)));
+    }
+
+    #[test]
+    fn test_user_listings_pruned_on_cancel() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+
+        let first = list(&env, &client, &seller, 1);
+        let second = list(&env, &client, &seller, 2);
+        let third = list(&env, &client, &seller, 3);
+        client.cancel_listing(&seller, &second);
+
+        assert_eq!(client.get_user_listings(&seller), Vec::from_array(&env, [first, third]));
+        assert_eq!(client.get_active_user_listings(&seller), Vec::from_array(&env, [first, third]));
+
+        // Lapsed listings drop out of the active view before anyone expires them
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        assert_eq!(client.get_active_user_listings(&seller).len(), 0);
+    }
+
+    #[test]
+    fn test_get_active_listings_filters_closed() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        let sold = client.create_listing(&seller, &nft, &1, &100, &currency, &3600);
+        let short = client.create_listing(&seller, &nft, &2, &100, &currency, &10);
+        let active = client.create_listing(&seller, &nft, &3, &100, &currency, &3600);
+        let cancelled = client.create_listing(&seller, &nft, &4, &100, &currency, &3600);
+        let last = client.create_listing(&seller, &nft, &5, &100, &currency, &3600);
+
+        client.purchase_listing(&buyer, &sold, &false);
+        client.cancel_listing(&seller, &cancelled);
+        env.ledger().with_mut(|li| li.timestamp = 11);
+
+        let (page, next) = client.get_active_listings(&1, &10);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page.get(0).unwrap().id, active);
+        assert_eq!(page.get(1).unwrap().id, last);
+        assert_eq!(next, last + 1);
+        assert_eq!(client.get_listing(&short).unwrap().status, ListingStatus::Active);
+
+        // The limit caps the IDs scanned, so a page over closed listings comes back empty
+        let (page, next) = client.get_active_listings(&1, &2);
+        assert_eq!(page.len(), 0);
+        assert_eq!(next, active);
+        let (page, next) = client.get_active_listings(&next, &2);
+        assert_eq!(page.len(), 1);
+        assert_eq!(next, last);
+        assert_eq!(client.get_active_listings(&(last + 1), &10), (Vec::new(&env), last + 1));
+    }
+
+    fn auction_setup(env: &Env) -> (AdvancedMarketplaceClient<'_>, Address, Address, Address, u64) {
+        let client = setup(env);
+        let seller = Address::generate(env);
+        let nft = create_nft(env, &seller, 10);
+        let currency = create_token(env);
+
+        let listing_id = client.create_auction(&seller, &nft, &1, &100, &currency, &3600);
+        (client, seller, nft, currency, listing_id)
+    }
+
+    fn bidder(env: &Env, currency: &Address) -> Address {
+        let bidder = Address::generate(env);
+        mint(env, currency, &bidder, 1000);
+        bidder
+    }
+
+    #[test]
+    fn test_outbid_refunds_previous_bidder() {
+        let env = Env::default();
+        let (client, _seller, _nft, currency, listing_id) = auction_setup(&env);
+        let currency_client = token::Client::new(&env, &currency);
+        let first = bidder(&env, &currency);
+        let second = bidder(&env, &currency);
+
+        assert_eq!(client.try_place_bid(&first, &listing_id, &99), Err(Ok(MarketplaceError::BidTooLow)));
+        client.place_bid(&first, &listing_id, &150);
+        assert_eq!(currency_client.balance(&first), 850);
+        assert_eq!(client.try_place_bid(&second, &listing_id, &150), Err(Ok(MarketplaceError::BidTooLow)));
+
+        client.place_bid(&second, &listing_id, &200);
+        assert_eq!(currency_client.balance(&first), 1000);
+        assert_eq!(currency_client.balance(&client.address), 200);
+        let auction = client.get_auction(&listing_id).unwrap();
+        assert!(auction.has_bid);
+        assert_eq!(auction.highest_bidder, second);
+
+        let result = client.try_purchase_listing(&first, &listing_id, &false);
+        assert_eq!(result, Err(Ok(MarketplaceError::AuctionListing)));
+    }
+
+    #[test]
+    fn test_settle_auction_pays_seller_and_winner() {
+        let env = Env::default();
+        let (client, seller, nft, currency, listing_id) = auction_setup(&env);
+        let currency_client = token::Client::new(&env, &currency);
+        let winner = bidder(&env, &currency);
+        client.place_bid(&winner, &listing_id, &400);
+
+        assert_eq!(client.try_settle_auction(&listing_id), Err(Ok(MarketplaceError::AuctionNotEnded)));
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        client.settle_auction(&listing_id);
+
+        // 2.5% fee on the 400 winning bid
+        assert_eq!(currency_client.balance(&seller), 390);
+        assert_eq!(currency_client.balance(&client.get_treasury().unwrap()), 10);
+        assert_eq!(currency_client.balance(&client.address), 0);
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), winner);
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Sold);
+    }
+
+    #[test]
+    fn test_settle_auction_without_bids_expires() {
+        let env = Env::default();
+        let (client, seller, nft, _currency, listing_id) = auction_setup(&env);
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        client.settle_auction(&listing_id);
+
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Expired);
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), seller);
+    }
+
+    #[test]
+    fn test_settle_auctions_skips_running() {
+        let env = Env::default();
+        let (client, seller, nft, currency, ended) = auction_setup(&env);
+        let running = client.create_auction(&seller, &nft, &2, &100, &currency, &7200);
+        let winner = bidder(&env, &currency);
+        client.place_bid(&winner, &ended, &400);
+        client.place_bid(&winner, &running, &300);
+        let fixed = list(&env, &client, &seller, 1);
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        let settled = client.settle_auctions(&Vec::from_array(&env, [ended, running, fixed, 99]));
+
+        assert_eq!(settled, 1);
+        assert_eq!(client.get_listing(&ended).unwrap().status, ListingStatus::Sold);
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), winner);
+        assert_eq!(client.get_listing(&running).unwrap().status, ListingStatus::Active);
+        assert_eq!(client.get_listing(&fixed).unwrap().status, ListingStatus::Active);
+
+        let too_many = Vec::from_array(&env, [ended; MAX_BATCH_SIZE as usize + 1]);
+        assert_eq!(client.try_settle_auctions(&too_many), Err(Ok(MarketplaceError::BatchTooLarge)));
+    }
+
+    #[test]
+    fn test_failed_outbid_refund_is_owed() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let (currency, currency_client) = create_blocklist_currency(&env);
+        let listing_id = client.create_auction(&seller, &create_nft(&env, &seller, 1), &1, &100, &currency, &3600);
+        let (first, second) = (Address::generate(&env), Address::generate(&env));
+        currency_client.mint(&first, &1000);
+        currency_client.mint(&second, &1000);
+        client.place_bid(&first, &listing_id, &150);
+
+        // A blocked account can't receive the refund, which must not block the new bid
+        currency_client.block_user(&first);
+        client.place_bid(&second, &listing_id, &200);
+        assert_eq!(client.pending_refund(&first, &currency), 150);
+
+        currency_client.unblock_user(&first);
+        assert_eq!(client.withdraw_refund(&first, &currency), 150);
+        assert_eq!(currency_client.balance(&first), 1000);
+        assert_eq!(client.pending_refund(&first, &currency), 0);
+    }
+
+    #[test]
+    fn test_settlement_completes_when_seller_payout_fails() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 1);
+        let (currency, currency_client) = create_blocklist_currency(&env);
+        let listing_id = client.create_auction(&seller, &nft, &1, &100, &currency, &3600);
+        let winner = Address::generate(&env);
+        currency_client.mint(&winner, &1000);
+        client.place_bid(&winner, &listing_id, &400);
+
+        // The seller can't be paid, but the winner still gets the NFT
+        currency_client.block_user(&seller);
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        client.settle_auction(&listing_id);
+
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), winner);
+        assert_eq!(currency_client.balance(&client.get_treasury().unwrap()), 10);
+        assert_eq!(client.pending_refund(&seller, &currency), 390);
+
+        currency_client.unblock_user(&seller);
+        assert_eq!(client.withdraw_refund(&seller, &currency), 390);
+        assert_eq!(currency_client.balance(&seller), 390);
+    }
+
+    #[test]
+    fn test_reclaim_unsettled_bid_after_grace_period() {
+        let env = Env::default();
+        let (client, seller, nft, currency, listing_id) = auction_setup(&env);
+        let bidder = bidder(&env, &currency);
+        client.place_bid(&bidder, &listing_id, &400);
+
+        env.ledger().with_mut(|li| li.timestamp = 3601);
+        assert_eq!(client.try_reclaim_bid(&listing_id), Err(Ok(MarketplaceError::AuctionNotEnded)));
+
+        env.ledger().with_mut(|li| li.timestamp = 3601 + SETTLEMENT_GRACE_PERIOD);
+        client.reclaim_bid(&listing_id);
+
+        assert_eq!(token::Client::new(&env, &currency).balance(&bidder), 1000);
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), seller);
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Cancelled);
+        assert_eq!(client.try_settle_auction(&listing_id), Err(Ok(MarketplaceError::ListingNotActive)));
+    }
+
+    #[test]
+    fn test_accept_offer_refunds_other_offers() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        let currency_client = token::Client::new(&env, &currency);
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &currency, &3600);
+
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+        mint(&env, &currency, &winner, 1000);
+        mint(&env, &currency, &loser, 1000);
+        client.make_offer(&winner, &listing_id, &800, &600);
+        client.make_offer(&loser, &listing_id, &700, &600);
+        assert_eq!(currency_client.balance(&client.address), 1500);
+
+        client.accept_offer(&seller, &listing_id, &winner);
+
+        // 2.5% fee on the 800 offer
+        assert_eq!(currency_client.balance(&seller), 780);
+        assert_eq!(currency_client.balance(&client.get_treasury().unwrap()), 20);
+        assert_eq!(currency_client.balance(&loser), 1000);
+        assert_eq!(currency_client.balance(&client.address), 0);
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), winner);
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Sold);
+        assert_eq!(client.get_offer(&listing_id, &loser), None);
+    }
+
+    #[test]
+    fn test_expired_offer_cannot_be_accepted() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let currency = create_token(&env);
+        let listing_id = client.create_listing(&seller, &create_nft(&env, &seller, 1), &1, &1000, &currency, &3600);
+
+        let buyer = Address::generate(&env);
+        mint(&env, &currency, &buyer, 1000);
+        client.make_offer(&buyer, &listing_id, &500, &600);
+
+        env.ledger().with_mut(|li| li.timestamp = 601);
+        let result = client.try_accept_offer(&seller, &listing_id, &buyer);
+        assert_eq!(result, Err(Ok(MarketplaceError::OfferExpired)));
+
+        client.cancel_offer(&buyer, &listing_id);
+        assert_eq!(token::Client::new(&env, &currency).balance(&buyer), 1000);
+    }
+
+    #[test]
+    fn test_offers_bounded_per_listing() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let currency = create_token(&env);
+        let listing_id = client.create_listing(&seller, &create_nft(&env, &seller, 1), &1, &1000, &currency, &3600);
+
+        let offer = |amount: i128| {
+            let buyer = Address::generate(&env);
+            mint(&env, &currency, &buyer, amount);
+            client.try_make_offer(&buyer, &listing_id, &amount, &600)
+        };
+
+        // Dust offers are rejected outright
+        assert_eq!(offer(99), Err(Ok(MarketplaceError::OfferTooLow)));
+
+        for _ in 0..MAX_OFFERS_PER_LISTING {
+            assert_eq!(offer(100), Ok(Ok(())));
+        }
+        assert_eq!(offer(900), Err(Ok(MarketplaceError::TooManyOffers)));
+
+        // Offers can't be made on a listing past its expiry
+        let late = client.create_listing(&seller, &create_nft(&env, &seller, 1), &1, &1000, &currency, &10);
+        env.ledger().with_mut(|li| li.timestamp = 11);
+        let buyer = Address::generate(&env);
+        mint(&env, &currency, &buyer, 1000);
+        let result = client.try_make_offer(&buyer, &late, &500, &600);
+        assert_eq!(result, Err(Ok(MarketplaceError::ListingExpired)));
+
+        // A price too large to scale by the minimum errors instead of overflowing
+        let huge = client.create_listing(&seller, &create_nft(&env, &seller, 1), &1, &i128::MAX, &currency, &3600);
+        let result = client.try_make_offer(&buyer, &huge, &500, &600);
+        assert_eq!(result, Err(Ok(MarketplaceError::ArithmeticOverflow)));
+    }
+
+    #[test]
+    fn test_cancel_listings_batch() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let other_seller = Address::generate(&env);
+
+        let first = list(&env, &client, &seller, 1);
+        let second = list(&env, &client, &seller, 2);
+        let foreign = list(&env, &client, &other_seller, 3);
+
+        let cancelled = client.cancel_listings(&seller, &Vec::from_array(&env, [first, second, foreign]));
+
+        assert_eq!(cancelled, 2);
+        let first_nft = client.get_listing(&first).unwrap().token_contract;
+        assert_eq!(AccessControlNFTClient::new(&env, &first_nft).owner_of(&1), seller);
+        assert_eq!(client.get_listing(&first).unwrap().status, ListingStatus::Cancelled);
+        assert_eq!(client.get_listing(&second).unwrap().status, ListingStatus::Cancelled);
+        assert_eq!(client.get_listing(&foreign).unwrap().status, ListingStatus::Active);
+    }
+
+    #[test]
+    fn test_volume_fee_tier_discount() {
+        let env = Env::default();
+        let tiers = Vec::from_array(&env, [FeeTier { min_volume: 1000, fee_bps: 100 }]);
+        let client = setup_with_tiers(&env, tiers);
+        let treasury = client.get_treasury().unwrap();
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+        let currency_client = token::Client::new(&env, &currency);
+
+        // First sale at the flat 2.5% fee
+        let first = client.create_listing(&seller, &nft, &1, &1000, &currency, &3600);
+        client.purchase_listing(&buyer, &first, &false);
+        assert_eq!(currency_client.balance(&treasury), 25);
+        assert_eq!(client.get_seller_volume(&seller, &currency), 1000);
+        assert_eq!(client.get_seller_fee(&seller, &currency), 100);
+
+        // Seller crossed the threshold, so the next sale pays 1%
+        let second = client.create_listing(&seller, &nft, &2, &1000, &currency, &3600);
+        client.purchase_listing(&buyer, &second, &false);
+        assert_eq!(currency_client.balance(&treasury), 35);
+
+        // Volume in one currency doesn't discount sales in another
+        let other_currency = create_token(&env);
+        assert_eq!(client.get_seller_volume(&seller, &other_currency), 0);
+        assert_eq!(client.get_seller_fee(&seller, &other_currency), 250);
+    }
+
+    #[test]
+    fn test_self_trade_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &seller, 10_000);
+        approve(&env, &client, &currency, &seller, 10_000);
+
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &currency, &3600);
+        let result = client.try_purchase_listing(&seller, &listing_id, &false);
+        assert_eq!(result, Err(Ok(MarketplaceError::SelfTrade)));
+        let result = client.try_make_offer(&seller, &listing_id, &500, &3600);
+        assert_eq!(result, Err(Ok(MarketplaceError::SelfTrade)));
+
+        let auction_id = client.create_auction(&seller, &nft, &2, &100, &currency, &3600);
+        let result = client.try_place_bid(&seller, &auction_id, &100);
+        assert_eq!(result, Err(Ok(MarketplaceError::SelfTrade)));
+        assert_eq!(client.get_seller_volume(&seller, &currency), 0);
+    }
+
+    #[test]
+    fn test_fee_tier_above_100_percent_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = AdvancedMarketplaceClient::new(&env, &env.register_contract(None, AdvancedMarketplace));
+        let tiers = Vec::from_array(&env, [FeeTier { min_volume: 1000, fee_bps: 10001 }]);
+        let result = client.try_initialize(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &250,
+            &tiers,
+            &LoyaltyConfig { points_per_purchase: 0, rebate_bps_per_point: 0 },
+        );
+        assert_eq!(result, Err(Ok(MarketplaceError::InvalidFee)));
+    }
+
+    #[test]
+    fn test_reentrant_purchase_rejected_by_host() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 1);
+        let currency = env.register_contract(None, ReentrantCurrency);
+        let currency_client = ReentrantCurrencyClient::new(&env, &currency);
+
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &currency, &3600);
+        currency_client.set_listing(&listing_id);
+        client.purchase_listing(&buyer, &listing_id, &false);
+
+        // The host refuses to re-enter a contract already on the call stack, before any
+        // marketplace code runs; the outer purchase completes once
+        assert!(currency_client.reentry_rejected_by_host());
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Sold);
+        assert_eq!(AccessControlNFTClient::new(&env, &nft).owner_of(&1), buyer);
+        assert_eq!(client.get_seller_volume(&seller, &currency), 1000);
+    }
+
+    #[test]
+    fn test_purchase_transfers_nft_ownership() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 3);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        let listing_id = client.create_listing(&seller, &nft, &2, &1000, &currency, &3600);
+        let nft_client = AccessControlNFTClient::new(&env, &nft);
+        assert_eq!(nft_client.owner_of(&2), client.address);
+        client.purchase_listing(&buyer, &listing_id, &false);
+
+        // Only the listed token changes hands
+        assert_eq!(nft_client.owner_of(&2), buyer);
+        assert_eq!(nft_client.owner_of(&1), seller);
+        assert_eq!(nft_client.balance_of(&seller), 2);
+        assert_eq!(nft_client.balance_of(&buyer), 1);
+    }
+
+    #[test]
+    fn test_royalty_three_way_split() {
+        let env = Env::default();
+        let client = setup(&env);
+        let treasury = client.get_treasury().unwrap();
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &currency, &3600);
+        client.set_royalty(&listing_id, &Royalty { recipient: creator.clone(), bps: 500 });
+        client.purchase_listing(&buyer, &listing_id, &false);
+
+        // 2.5% fee, 5% royalty, remainder to the seller
+        let currency_client = token::Client::new(&env, &currency);
+        let (fee, royalty, proceeds) = (
+            currency_client.balance(&treasury),
+            currency_client.balance(&creator),
+            currency_client.balance(&seller),
+        );
+        assert_eq!((fee, royalty, proceeds), (25, 50, 925));
+        assert_eq!(fee + royalty + proceeds, 1000);
+        assert_eq!(currency_client.balance(&buyer), 9000);
+    }
+
+    #[test]
+    fn test_royalty_over_limit_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let listing_id = list(&env, &client, &Address::generate(&env), 1);
+
+        let royalty = Royalty { recipient: Address::generate(&env), bps: 9800 };
+        let result = client.try_set_royalty(&listing_id, &royalty);
+        assert_eq!(result, Err(Ok(MarketplaceError::InvalidRoyalty)));
+        assert_eq!(client.get_royalty(&listing_id), None);
+    }
+
+    #[test]
+    fn test_royalty_locked_once_bid_or_offer() {
+        let env = Env::default();
+        let (client, seller, nft, currency, auction_id) = auction_setup(&env);
+        let royalty = Royalty { recipient: Address::generate(&env), bps: 500 };
+
+        client.set_royalty(&auction_id, &royalty);
+        client.place_bid(&bidder(&env, &currency), &auction_id, &400);
+        let result = client.try_set_royalty(&auction_id, &Royalty { recipient: seller.clone(), bps: 0 });
+        assert_eq!(result, Err(Ok(MarketplaceError::RoyaltyLocked)));
+        assert_eq!(client.get_royalty(&auction_id), Some(royalty.clone()));
+
+        let listing_id = client.create_listing(&seller, &nft, &2, &1000, &currency, &3600);
+        client.make_offer(&bidder(&env, &currency), &listing_id, &500, &600);
+        let result = client.try_set_royalty(&listing_id, &royalty);
+        assert_eq!(result, Err(Ok(MarketplaceError::RoyaltyLocked)));
+    }
+
+    #[test]
+    fn test_sale_split_among_payees() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        let (artist, producer, curator) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+        let payees = Vec::from_array(&env, [(artist.clone(), 5000), (producer.clone(), 3000), (curator.clone(), 2000)]);
+        let listing_id = client.create_listing(&seller, &nft, &1, &10_000, &currency, &3600);
+        client.set_payees(&listing_id, &payees);
+        client.purchase_listing(&buyer, &listing_id, &false);
+
+        // 2.5% fee leaves 9750 to split 50/30/20
+        let currency_client = token::Client::new(&env, &currency);
+        assert_eq!(currency_client.balance(&artist), 4875);
+        assert_eq!(currency_client.balance(&producer), 2925);
+        assert_eq!(currency_client.balance(&curator), 1950);
+        assert_eq!(currency_client.balance(&seller), 0);
+        assert_eq!(currency_client.balance(&client.get_treasury().unwrap()), 250);
+
+        // The split belongs to that listing, so the buyer's resale pays the buyer
+        let resale = client.create_listing(&buyer, &nft, &1, &1000, &currency, &3600);
+        assert_eq!(client.get_payees(&resale).len(), 0);
+        let next_buyer = Address::generate(&env);
+        mint(&env, &currency, &next_buyer, 1000);
+        approve(&env, &client, &currency, &next_buyer, 1000);
+        client.purchase_listing(&next_buyer, &resale, &false);
+        assert_eq!(currency_client.balance(&buyer), 975);
+        assert_eq!(currency_client.balance(&artist), 4875);
+    }
+
+    #[test]
+    fn test_set_payees_rejects_bad_shares() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let listing_id = list(&env, &client, &seller, 1);
+
+        let payees = Vec::from_array(&env, [(Address::generate(&env), 5000), (Address::generate(&env), 4000)]);
+        let result = client.try_set_payees(&listing_id, &payees);
+        assert_eq!(result, Err(Ok(MarketplaceError::InvalidPayees)));
+
+        // Shares that wrap the u32 sum around to 10000 are rejected too
+        let payees = Vec::from_array(&env, [(Address::generate(&env), u32::MAX), (Address::generate(&env), 10001)]);
+        let result = client.try_set_payees(&listing_id, &payees);
+        assert_eq!(result, Err(Ok(MarketplaceError::InvalidPayees)));
+    }
+
+    #[test]
+    fn test_set_payees_requires_seller() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let listing_id = list(&env, &client, &seller, 1);
+        let owner = client.get_owner().unwrap();
+        let payees = Vec::from_array(&env, [(owner.clone(), 10000)]);
+
+        // Only the marketplace owner signs, so the seller's auth check fails
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &owner,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "set_payees",
+                    args: (listing_id, payees.clone()).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_set_payees(&listing_id, &payees);
+        assert!(result.is_err());
+        assert_eq!(client.get_payees(&listing_id).len(), 0);
+    }
+
+    #[test]
+    fn test_sweep_collection_cheapest_within_budget() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        let expensive = client.create_listing(&seller, &nft, &1, &900, &currency, &3600);
+        let cheapest = client.create_listing(&seller, &nft, &2, &100, &currency, &3600);
+        let middle = client.create_listing(&seller, &nft, &3, &300, &currency, &3600);
+        let second = client.create_listing(&seller, &nft, &4, &200, &currency, &3600);
+
+        let purchased = client.sweep_collection(&buyer, &nft, &2, &1000);
+        assert_eq!(purchased, Vec::from_array(&env, [cheapest, second]));
+        assert_eq!(client.get_listing(&cheapest).unwrap().status, ListingStatus::Sold);
+        assert_eq!(client.get_listing(&second).unwrap().status, ListingStatus::Sold);
+        assert_eq!(client.get_listing(&middle).unwrap().status, ListingStatus::Active);
+
+        // Budget only covers the next cheapest listing
+        let purchased = client.sweep_collection(&buyer, &nft, &5, &500);
+        assert_eq!(purchased, Vec::from_array(&env, [middle]));
+        assert_eq!(client.get_listing(&expensive).unwrap().status, ListingStatus::Active);
+    }
+
+    #[test]
+    fn test_purchase_rejects_underfunded_buyer() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 500);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &currency, &3600);
+        let result = client.try_purchase_listing(&buyer, &listing_id, &false);
+
+        assert_eq!(result, Err(Ok(MarketplaceError::InsufficientFunds)));
+        assert_eq!(token::Client::new(&env, &currency).balance(&buyer), 500);
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Active);
+    }
+
+    #[test]
+    fn test_purchase_rejects_underapproved_buyer() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 999);
+
+        let listing_id = client.create_listing(&seller, &nft, &1, &1000, &currency, &3600);
+        let result = client.try_purchase_listing(&buyer, &listing_id, &false);
+
+        assert_eq!(result, Err(Ok(MarketplaceError::InsufficientFunds)));
+        assert_eq!(token::Client::new(&env, &currency).balance(&buyer), 10_000);
+        assert_eq!(client.get_listing(&listing_id).unwrap().status, ListingStatus::Active);
+    }
+
+    #[test]
+    fn test_loyalty_points_rebate_fee() {
+        let env = Env::default();
+        let loyalty = LoyaltyConfig { points_per_purchase: 1, rebate_bps_per_point: 50 };
+        let client = setup_with_config(&env, Vec::new(&env), loyalty);
+        let treasury = client.get_treasury().unwrap();
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+        let currency_client = token::Client::new(&env, &currency);
+
+        for token_id in 1..=2u64 {
+            let listing_id = client.create_listing(&seller, &nft, &token_id, &1000, &currency, &3600);
+            client.purchase_listing(&buyer, &listing_id, &false);
+        }
+        assert_eq!(client.buyer_points(&buyer), 2);
+        assert_eq!(currency_client.balance(&treasury), 50);
+
+        // Two points rebate 100 bps, so the fee drops from 2.5% to 1.5%
+        let listing_id = client.create_listing(&seller, &nft, &3, &1000, &currency, &3600);
+        client.purchase_listing(&buyer, &listing_id, &true);
+        assert_eq!(currency_client.balance(&treasury), 65);
+        assert_eq!(currency_client.balance(&buyer), 10_000 - 2000 - 990);
+        assert_eq!(client.buyer_points(&buyer), 1);
+
+        // Points live as long as listings do, not just the default entry TTL
+        let extended = env.ledger().sequence() + LISTING_TTL_EXTEND;
+        assert_eq!(live_until(&env, &client, &DataKey::BuyerPoints(buyer)), extended);
+    }
+
+    #[test]
+    fn test_floor_price_tracking() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, 10);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        assert_eq!(client.floor_price(&nft), None);
+
+        client.create_listing(&seller, &nft, &1, &500, &currency, &3600);
+        assert_eq!(client.floor_price(&nft), Some(500));
+
+        let cheapest = client.create_listing(&seller, &nft, &2, &200, &currency, &3600);
+        client.create_listing(&seller, &nft, &3, &300, &currency, &3600);
+        assert_eq!(client.floor_price(&nft), Some(200));
+
+        // Selling the floor listing recomputes from the remaining ones
+        client.purchase_listing(&buyer, &cheapest, &false);
+        assert_eq!(client.floor_price(&nft), Some(300));
+
+        // A floor listing that lapses stops counting before anyone expires it
+        client.create_listing(&seller, &nft, &4, &100, &currency, &10);
+        assert_eq!(client.floor_price(&nft), Some(100));
+        env.ledger().with_mut(|li| li.timestamp = 11);
+        assert_eq!(client.floor_price(&nft), Some(300));
+    }
+
+    #[test]
+    fn test_collection_index_keeps_cheapest() {
+        let env = Env::default();
+        // The test budget is shared across every call, and this fills a collection
+        env.budget().reset_unlimited();
+        let client = setup(&env);
+
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let nft = create_nft(&env, &seller, MAX_COLLECTION_LISTINGS + 2);
+        let currency = create_token(&env);
+        mint(&env, &currency, &buyer, 10_000);
+        approve(&env, &client, &currency, &buyer, 10_000);
+
+        for token_id in 1..=MAX_COLLECTION_LISTINGS as u64 {
+            client.create_listing(&seller, &nft, &token_id, &100, &currency, &3600);
+        }
+
+        // Listings past the index size are still created, and a cheaper one becomes the floor
+        let next = MAX_COLLECTION_LISTINGS as u64 + 1;
+        let cheap = client.create_listing(&seller, &nft, &next, &50, &currency, &3600);
+        assert_eq!(client.floor_price(&nft), Some(50));
+
+        // A pricier listing is left out of the full index without moving the floor
+        let pricey = client.create_listing(&seller, &nft, &(next + 1), &200, &currency, &3600);
+        assert_eq!(client.get_listing(&pricey).unwrap().status, ListingStatus::Active);
+        assert_eq!(client.floor_price(&nft), Some(50));
+        assert_eq!(client.index_listings(&Vec::from_array(&env, [pricey])), 0);
+
+        // Once a sale frees a slot, the pricier listing can be indexed
+        client.purchase_listing(&buyer, &cheap, &false);
+        assert_eq!(client.floor_price(&nft), Some(100));
+        assert_eq!(client.index_listings(&Vec::from_array(&env, [pricey, cheap])), 1);
+        assert_eq!(client.index_listings(&Vec::from_array(&env, [pricey])), 0);
+    }
+
+    #[test]
+    fn test_upgrade_rejects_non_owner() {
+        let env = Env::default();
+        let client = setup(&env);
+        let outsider = Address::generate(&env);
+        let new_wasm_hash = BytesN::from_array(&env, &[7; 32]);
+
+        // Only the outsider signs, so the owner's auth check fails before the WASM is swapped
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &outsider,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "upgrade",
+                    args: (new_wasm_hash.clone(),).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_upgrade(&new_wasm_hash);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_upgrade_requires_initialization() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AdvancedMarketplace);
+        let client = AdvancedMarketplaceClient::new(&env, &contract_id);
+
+        let result = client.try_upgrade(&BytesN::from_array(&env, &[7; 32]));
+        assert_eq!(result, Err(Ok(MarketplaceError::NotInitialized)));
+    }
+
+    /// Ledger sequence up to which one of the marketplace's persistent entries stays live
+    fn live_until(env: &Env, client: &AdvancedMarketplaceClient, key: &DataKey) -> u32 {
+        let ledger_key = xdr::LedgerKey::ContractData(xdr::LedgerKeyContractData {
+            contract: (&client.address).try_into().unwrap(),
+            key: key.try_into().unwrap(),
+            durability: xdr::ContractDataDurability::Persistent,
+        });
+        env.to_ledger_snapshot()
+            .ledger_entries
+            .iter()
+            .find(|(entry_key, _)| **entry_key == ledger_key)
+            .and_then(|(_, (_, live_until))| *live_until)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bump_listing_ttl_keeps_listing_alive() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let listing_id = list(&env, &client, &seller, 1);
+
+        // Just before the TTL set when the listing was created runs out
+        env.ledger().with_mut(|li| li.sequence_number += LISTING_TTL_EXTEND - 10);
+        client.bump_listing_ttl(&listing_id);
+
+        let bumped = env.ledger().sequence() + LISTING_TTL_EXTEND;
+        assert_eq!(live_until(&env, &client, &DataKey::Listing(listing_id)), bumped);
+        assert_eq!(live_until(&env, &client, &DataKey::UserListings(seller)), bumped);
+
+        assert_eq!(client.try_bump_listing_ttl(&99), Err(Ok(MarketplaceError::ListingNotFound)));
+    }
+
+    #[test]
+    fn test_bump_listing_ttl_keeps_auction_escrow_alive() {
+        let env = Env::default();
+        let (client, _seller, _nft, currency, listing_id) = auction_setup(&env);
+        client.place_bid(&bidder(&env, &currency), &listing_id, &400);
+
+        env.ledger().with_mut(|li| li.sequence_number += LISTING_TTL_EXTEND - 10);
+        client.bump_listing_ttl(&listing_id);
+
+        let bumped = env.ledger().sequence() + LISTING_TTL_EXTEND;
+        assert_eq!(live_until(&env, &client, &DataKey::Auction(listing_id)), bumped);
+    }
+
+    #[test]
+    fn test_currency_allowlist() {
+        let env = Env::default();
+        let client = setup(&env);
+        let seller = Address::generate(&env);
+        let (accepted, other) = (Address::generate(&env), Address::generate(&env));
+
+        // Nothing configured yet, so any currency is accepted
+        assert!(client.is_accepted_currency(&other));
+
+        client.add_currency(&accepted);
+        assert!(client.is_accepted_currency(&accepted));
+        assert!(!client.is_accepted_currency(&other));
+
+        client.create_listing(&seller, &create_nft(&env, &seller, 1), &1, &1000, &accepted, &3600);
+        let result = client.try_create_listing(&seller, &Address::generate(&env), &2, &1000, &other, &3600);
+        assert_eq!(result, Err(Ok(MarketplaceError::CurrencyNotAccepted)));
+        let result = client.try_create_auction(&seller, &Address::generate(&env), &3, &1000, &other, &3600);
+        assert_eq!(result, Err(Ok(MarketplaceError::CurrencyNotAccepted)));
+
+        // Removing the last currency restores the accept-any fallback
+        client.remove_currency(&accepted);
+        assert!(client.is_accepted_currency(&other));
+    }
+}
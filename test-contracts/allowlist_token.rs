@@ -1,6 +1,6 @@
 // Simplified allowlist token contract demonstrating allowlist pattern
 // Uses basic Soroban SDK features without OpenZeppelin dependencies
-use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, Env, Address, Symbol};
 
 #[contracttype]
 #[derive(Clone)]
@@ -9,6 +9,7 @@ pub enum DataKey {
     Allowance(Address, Address),
     Admin,
     Allowlist(Address),
+    Manager(Address),
     TokenName,
     TokenSymbol,
     TokenDecimals,
@@ -23,6 +24,20 @@ pub enum AllowlistEvent {
     AllowlistTransfer(Address, Address, i128),
 }
 
+/// Reasons a mutating allowlist token call can be rejected
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AllowlistError {
+    NotInitialized = 1,
+    NotAllowed = 2,
+    InsufficientBalance = 3,
+    NegativeAmount = 4,
+    Unauthorized = 5,
+    AlreadyInitialized = 6,
+    InsufficientAllowance = 7,
+}
+
 #[contract]
 pub struct AllowlistToken;
 
@@ -36,9 +51,12 @@ impl AllowlistToken {
         symbol: Symbol,
         decimals: u32,
         total_supply: i128,
-    ) {
+    ) -> Result<(), AllowlistError> {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            return Err(AllowlistError::AlreadyInitialized);
+        }
+        if total_supply < 0 {
+            return Err(AllowlistError::NegativeAmount);
         }
         
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -50,6 +68,8 @@ impl AllowlistToken {
         
         // Admin is automatically allowed
         env.storage().instance().set(&DataKey::Allowlist(admin.clone()), &true);
+        
+        Ok(())
     }
     
     /// Check if user is in allowlist
@@ -57,47 +77,94 @@ impl AllowlistToken {
         env.storage().instance().get(&DataKey::Allowlist(user)).unwrap_or(false)
     }
     
-    /// Add user to allowlist (admin only)
-    pub fn allow_user(env: Env, user: Address) {
+    /// Check if account can manage the allowlist (admin is an implicit manager)
+    pub fn is_manager(env: Env, account: Address) -> bool {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        account == admin || env.storage().instance().get(&DataKey::Manager(account)).unwrap_or(false)
+    }
+    
+    /// Grant the allowlist manager role (admin only)
+    pub fn grant_manager(env: Env, account: Address) -> Result<(), AllowlistError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::Manager(account.clone()), &true);
+        env.events().publish((Symbol::new(&env, "manager_granted"),), account);
+        Ok(())
+    }
+    
+    /// Revoke the allowlist manager role (admin only)
+    pub fn revoke_manager(env: Env, account: Address) -> Result<(), AllowlistError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
+        env.storage().instance().set(&DataKey::Manager(account.clone()), &false);
+        env.events().publish((Symbol::new(&env, "manager_revoked"),), account);
+        Ok(())
+    }
+    
+    /// Add user to allowlist (manager only)
+    pub fn allow_user(env: Env, manager: Address, user: Address) -> Result<(), AllowlistError> {
+        Self::require_manager(&env, &manager)?;
+        
         env.storage().instance().set(&DataKey::Allowlist(user.clone()), &true);
         env.events().publish((Symbol::new(&env, "user_allowed"),), user);
+        Ok(())
     }
     
-    /// Remove user from allowlist (admin only)
-    pub fn disallow_user(env: Env, user: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+    /// Remove user from allowlist (manager only)
+    pub fn disallow_user(env: Env, manager: Address, user: Address) -> Result<(), AllowlistError> {
+        Self::require_manager(&env, &manager)?;
         
         env.storage().instance().set(&DataKey::Allowlist(user.clone()), &false);
         env.events().publish((Symbol::new(&env, "user_disallowed"),), user);
+        Ok(())
     }
     
     /// Transfer tokens (only between allowed users)
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), AllowlistError> {
         from.require_auth();
         
-        // Check allowlist
-        if !Self::allowed(env.clone(), from.clone()) {
-            panic!("From address not allowed");
-        }
-        if !Self::allowed(env.clone(), to.clone()) {
-            panic!("To address not allowed");
-        }
+        Self::transfer_checked(&env, from, to, amount)
+    }
+    
+    /// Approve a spender to transfer tokens on behalf of `from`
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128) -> Result<(), AllowlistError> {
+        from.require_auth();
+        Self::admin(&env)?;
         
-        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
-        if from_balance < amount {
-            panic!("Insufficient balance");
+        if amount < 0 {
+            return Err(AllowlistError::NegativeAmount);
         }
         
-        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        env.storage().instance().set(&DataKey::Allowance(from.clone(), spender.clone()), &amount);
+        env.events().publish((Symbol::new(&env, "approve"),), (from, spender, amount));
+        Ok(())
+    }
+    
+    /// Get the amount a spender may transfer on behalf of `from`
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        env.storage().instance().get(&DataKey::Allowance(from, spender)).unwrap_or(0)
+    }
+    
+    /// Transfer tokens on behalf of `from` using the spender's allowance (only between allowed users)
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), AllowlistError> {
+        spender.require_auth();
         
-        env.storage().instance().set(&DataKey::Balance(from.clone()), &(from_balance - amount));
-        env.storage().instance().set(&DataKey::Balance(to.clone()), &(to_balance + amount));
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(AllowlistError::InsufficientAllowance);
+        }
         
-        env.events().publish((Symbol::new(&env, "transfer"),), (from, to, amount));
+        Self::transfer_checked(&env, from.clone(), to, amount)?;
+        env.storage().instance().set(&DataKey::Allowance(from, spender), &(allowance - amount));
+        Ok(())
     }
     
     /// Get balance
@@ -124,6 +191,47 @@ impl AllowlistToken {
     pub fn total_supply(env: Env) -> i128 {
         env.storage().instance().get(&DataKey::TotalSupply).unwrap()
     }
+    
+    fn admin(env: &Env) -> Result<Address, AllowlistError> {
+        env.storage().instance().get(&DataKey::Admin).ok_or(AllowlistError::NotInitialized)
+    }
+    
+    fn require_manager(env: &Env, manager: &Address) -> Result<(), AllowlistError> {
+        manager.require_auth();
+        
+        let admin = Self::admin(env)?;
+        let is_manager = env.storage().instance().get(&DataKey::Manager(manager.clone())).unwrap_or(false);
+        if *manager != admin && !is_manager {
+            return Err(AllowlistError::Unauthorized);
+        }
+        Ok(())
+    }
+    
+    fn transfer_checked(env: &Env, from: Address, to: Address, amount: i128) -> Result<(), AllowlistError> {
+        Self::admin(env)?;
+        
+        if amount < 0 {
+            return Err(AllowlistError::NegativeAmount);
+        }
+        
+        // Check allowlist
+        if !Self::allowed(env.clone(), from.clone()) || !Self::allowed(env.clone(), to.clone()) {
+            return Err(AllowlistError::NotAllowed);
+        }
+        
+        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
+        if from_balance < amount {
+            return Err(AllowlistError::InsufficientBalance);
+        }
+        
+        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        
+        env.storage().instance().set(&DataKey::Balance(from.clone()), &(from_balance - amount));
+        env.storage().instance().set(&DataKey::Balance(to.clone()), &(to_balance + amount));
+        
+        env.events().publish((Symbol::new(env, "transfer"),), (from, to, amount));
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -134,6 +242,7 @@ mod tests {
     #[test]
     fn test_allowlist_functionality() {
         let env = Env::default();
+        env.mock_all_auths();
         let contract_id = env.register_contract(None, AllowlistToken);
         let client = AllowlistTokenClient::new(&env, &contract_id);
         
@@ -155,15 +264,132 @@ mod tests {
         assert!(!client.allowed(&user1));
         
         // Allow user1
-        client.allow_user(&user1);
+        client.allow_user(&admin, &user1);
         assert!(client.allowed(&user1));
         
         // Test transfer between allowed users
         client.transfer(&admin, &user1, &1000);
         assert_eq!(client.balance(&user1), 1000);
         
+        // user2 was never allowed, so it cannot receive
+        let result = client.try_transfer(&user1, &user2, &100);
+        assert_eq!(result, Err(Ok(AllowlistError::NotAllowed)));
+        
         // Disallow user1 and test transfer failure
-        client.disallow_user(&user1);
+        client.disallow_user(&admin, &user1);
         assert!(!client.allowed(&user1));
+        let result = client.try_transfer(&user1, &admin, &100);
+        assert_eq!(result, Err(Ok(AllowlistError::NotAllowed)));
+    }
+    
+    fn setup(env: &Env) -> (AllowlistTokenClient<'_>, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AllowlistToken);
+        let client = AllowlistTokenClient::new(env, &contract_id);
+        
+        let admin = Address::generate(env);
+        client.initialize(
+            &admin,
+            &Symbol::new(env, "AllowToken"),
+            &Symbol::new(env, "ALT"),
+            &18,
+            &1000000,
+        );
+        (client, admin)
+    }
+    
+    #[test]
+    fn test_manager_can_modify_allowlist() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let manager = Address::generate(&env);
+        let user = Address::generate(&env);
+        
+        assert!(client.is_manager(&admin));
+        client.grant_manager(&manager);
+        assert!(client.is_manager(&manager));
+        
+        client.allow_user(&manager, &user);
+        assert!(client.allowed(&user));
+        
+        client.disallow_user(&manager, &user);
+        assert!(!client.allowed(&user));
+    }
+    
+    #[test]
+    fn test_non_manager_rejected() {
+        let env = Env::default();
+        let (client, _admin) = setup(&env);
+        
+        let result = client.try_allow_user(&Address::generate(&env), &Address::generate(&env));
+        assert_eq!(result, Err(Ok(AllowlistError::Unauthorized)));
+    }
+    
+    #[test]
+    fn test_transfer_error_codes() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let user = Address::generate(&env);
+        
+        let result = client.try_transfer(&admin, &user, &100);
+        assert_eq!(result, Err(Ok(AllowlistError::NotAllowed)));
+        
+        client.allow_user(&admin, &user);
+        let result = client.try_transfer(&admin, &user, &-1);
+        assert_eq!(result, Err(Ok(AllowlistError::NegativeAmount)));
+        
+        let result = client.try_transfer(&user, &admin, &1);
+        assert_eq!(result, Err(Ok(AllowlistError::InsufficientBalance)));
+    }
+    
+    #[test]
+    fn test_transfer_from_between_allowed_users() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let spender = Address::generate(&env);
+        let user = Address::generate(&env);
+        client.allow_user(&admin, &user);
+        
+        client.approve(&admin, &spender, &500);
+        assert_eq!(client.allowance(&admin, &spender), 500);
+        
+        client.transfer_from(&spender, &admin, &user, &200);
+        assert_eq!(client.balance(&user), 200);
+        assert_eq!(client.allowance(&admin, &spender), 300);
+        
+        let result = client.try_transfer_from(&spender, &admin, &user, &301);
+        assert_eq!(result, Err(Ok(AllowlistError::InsufficientAllowance)));
+    }
+    
+    #[test]
+    fn test_transfer_from_to_disallowed_recipient_rejected() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let spender = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        
+        client.approve(&admin, &spender, &500);
+        let result = client.try_transfer_from(&spender, &admin, &outsider, &100);
+        assert_eq!(result, Err(Ok(AllowlistError::NotAllowed)));
+        assert_eq!(client.balance(&outsider), 0);
+        assert_eq!(client.allowance(&admin, &spender), 500);
+    }
+    
+    #[test]
+    fn test_uninitialized_and_double_init_errors() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AllowlistToken);
+        let client = AllowlistTokenClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        
+        let result = client.try_grant_manager(&admin);
+        assert_eq!(result, Err(Ok(AllowlistError::NotInitialized)));
+        
+        let name = Symbol::new(&env, "AllowToken");
+        let symbol = Symbol::new(&env, "ALT");
+        client.initialize(&admin, &name, &symbol, &18, &1000000);
+        let result = client.try_initialize(&admin, &name, &symbol, &18, &1000000);
+        assert_eq!(result, Err(Ok(AllowlistError::AlreadyInitialized)));
     }
 }
@@ -1,18 +1,22 @@
 // Simplified blocklist token contract demonstrating blocklist pattern
 // Uses basic Soroban SDK features without OpenZeppelin dependencies
-use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, String, Symbol, Vec};
+use crate::token_error::TokenError;
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Balance(Address),
-    Allowance(Address, Address),
+    Allowance(Address, Address), // (amount, live_until_ledger)
     Admin,
     Blocklist(Address),
     TokenName,
     TokenSymbol,
     TokenDecimals,
     TotalSupply,
+    BurnAddress,
+    TotalBurned,
+    TrustedContract(Address),
 }
 
 #[contracttype]
@@ -23,6 +27,9 @@ pub enum BlocklistEvent {
     BlocklistTransfer(Address, Address, i128),
 }
 
+/// Maximum number of addresses in a single batch block/unblock call
+const MAX_BATCH_SIZE: u32 = 50;
+
 #[contract]
 pub struct BlocklistToken;
 
@@ -32,13 +39,13 @@ impl BlocklistToken {
     pub fn initialize(
         env: Env,
         admin: Address,
-        name: Symbol,
-        symbol: Symbol,
+        name: String,
+        symbol: String,
         decimals: u32,
         total_supply: i128,
-    ) {
+    ) -> Result<(), TokenError> {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            return Err(TokenError::AlreadyInitialized);
         }
         
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -47,6 +54,7 @@ impl BlocklistToken {
         env.storage().instance().set(&DataKey::TokenDecimals, &decimals);
         env.storage().instance().set(&DataKey::TotalSupply, &total_supply);
         env.storage().instance().set(&DataKey::Balance(admin.clone()), &total_supply);
+        Ok(())
     }
     
     /// Check if user is blocked
@@ -55,46 +63,207 @@ impl BlocklistToken {
     }
     
     /// Add user to blocklist (admin only)
-    pub fn block_user(env: Env, user: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn block_user(env: Env, user: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
         env.storage().instance().set(&DataKey::Blocklist(user.clone()), &true);
         env.events().publish((Symbol::new(&env, "user_blocked"),), user);
+        Ok(())
     }
     
     /// Remove user from blocklist (admin only)
-    pub fn unblock_user(env: Env, user: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn unblock_user(env: Env, user: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
         env.storage().instance().set(&DataKey::Blocklist(user.clone()), &false);
         env.events().publish((Symbol::new(&env, "user_unblocked"),), user);
+        Ok(())
+    }
+    
+    /// Add several users to the blocklist (admin only), skipping those already blocked
+    pub fn block_users(env: Env, users: Vec<Address>) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        if users.len() > MAX_BATCH_SIZE {
+            return Err(TokenError::BatchTooLarge);
+        }
+        
+        for user in users.iter() {
+            if !Self::blocked(env.clone(), user.clone()) {
+                env.storage().instance().set(&DataKey::Blocklist(user.clone()), &true);
+                env.events().publish((Symbol::new(&env, "user_blocked"),), user);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Remove several users from the blocklist (admin only), skipping those not blocked
+    pub fn unblock_users(env: Env, users: Vec<Address>) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        if users.len() > MAX_BATCH_SIZE {
+            return Err(TokenError::BatchTooLarge);
+        }
+        
+        for user in users.iter() {
+            if Self::blocked(env.clone(), user.clone()) {
+                env.storage().instance().set(&DataKey::Blocklist(user.clone()), &false);
+                env.events().publish((Symbol::new(&env, "user_unblocked"),), user);
+            }
+        }
+        Ok(())
     }
     
     /// Transfer tokens (blocked users cannot participate)
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
         from.require_auth();
         
-        // Check blocklist
-        if Self::blocked(env.clone(), from.clone()) {
-            panic!("From address is blocked");
+        Self::transfer_checked(&env, from, to, amount, false)
+    }
+    
+    /// Approve a spender to transfer tokens on behalf of `from` until `live_until_ledger`
+    pub fn approve(
+        env: Env,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        live_until_ledger: u32,
+    ) -> Result<(), TokenError> {
+        from.require_auth();
+        
+        if amount < 0 {
+            return Err(TokenError::InvalidAmount);
         }
-        if Self::blocked(env.clone(), to.clone()) {
-            panic!("To address is blocked");
+        if amount > 0 && live_until_ledger < env.ledger().sequence() {
+            return Err(TokenError::InvalidExpiration);
         }
         
-        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
-        if from_balance < amount {
-            panic!("Insufficient balance");
+        env.storage().instance().set(&DataKey::Allowance(from.clone(), spender.clone()), &(amount, live_until_ledger));
+        env.events().publish((Symbol::new(&env, "approve"),), (from, spender, amount, live_until_ledger));
+        Ok(())
+    }
+    
+    /// Get the amount a spender may transfer on behalf of `from` (0 once the approval has expired)
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        let (amount, live_until_ledger): (i128, u32) = env.storage().instance()
+            .get(&DataKey::Allowance(from, spender))
+            .unwrap_or((0, 0));
+        
+        if live_until_ledger < env.ledger().sequence() {
+            0
+        } else {
+            amount
+        }
+    }
+    
+    /// Transfer tokens on behalf of `from` using the spender's allowance; a blocked spender
+    /// cannot use an allowance granted before it was blocked. A trusted contract spender may
+    /// pull from a blocked `from`, but never deliver to a blocked `to`.
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        spender.require_auth();
+        
+        if Self::blocked(env.clone(), spender.clone()) {
+            return Err(TokenError::Blocked);
         }
         
-        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        let trusted = Self::is_trusted_contract(env.clone(), spender);
+        Self::transfer_checked(&env, from, to, amount, trusted)
+    }
+    
+    /// Check if an address is a trusted contract that may pull from blocked accounts via `transfer_from`
+    pub fn is_trusted_contract(env: Env, contract: Address) -> bool {
+        env.storage().instance().get(&DataKey::TrustedContract(contract)).unwrap_or(false)
+    }
+    
+    /// Add a trusted contract (admin only)
+    pub fn add_trusted_contract(env: Env, contract: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::TrustedContract(contract.clone()), &true);
+        env.events().publish((Symbol::new(&env, "trusted_contract_added"),), contract);
+        Ok(())
+    }
+    
+    /// Remove a trusted contract (admin only)
+    pub fn remove_trusted_contract(env: Env, contract: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        env.storage().instance().remove(&DataKey::TrustedContract(contract.clone()));
+        env.events().publish((Symbol::new(&env, "trusted_contract_removed"),), contract);
+        Ok(())
+    }
+    
+    /// Mint new tokens to a non-blocked account (admin only)
+    pub fn mint(env: Env, to: Address, amount: i128) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if Self::blocked(env.clone(), to.clone()) {
+            return Err(TokenError::Blocked);
+        }
+        
+        let to_balance = Self::balance(env.clone(), to.clone());
+        let total_supply = Self::total_supply(env.clone());
+        let new_balance = to_balance.checked_add(amount).ok_or(TokenError::Overflow)?;
+        let new_supply = total_supply.checked_add(amount).ok_or(TokenError::Overflow)?;
+        
+        env.storage().instance().set(&DataKey::Balance(to.clone()), &new_balance);
+        env.storage().instance().set(&DataKey::TotalSupply, &new_supply);
+        
+        env.events().publish((Symbol::new(&env, "mint"),), (to, amount));
+        Ok(())
+    }
+    
+    /// Burn tokens, removing them from the holder's balance and from supply
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        from.require_auth();
+        
+        Self::burn_checked(&env, from, amount)
+    }
+    
+    /// Burn tokens on behalf of `from` using the spender's allowance; a blocked spender
+    /// cannot use an allowance granted before it was blocked
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) -> Result<(), TokenError> {
+        spender.require_auth();
+        
+        if Self::blocked(env.clone(), spender.clone()) {
+            return Err(TokenError::Blocked);
+        }
         
-        env.storage().instance().set(&DataKey::Balance(from.clone()), &(from_balance - amount));
-        env.storage().instance().set(&DataKey::Balance(to.clone()), &(to_balance + amount));
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        Self::burn_checked(&env, from, amount)
+    }
+    
+    /// Set the burn address reported in burn events (admin only)
+    pub fn set_burn_address(env: Env, burn_address: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
         
-        env.events().publish((Symbol::new(&env, "transfer"),), (from, to, amount));
+        env.storage().instance().set(&DataKey::BurnAddress, &burn_address);
+        env.events().publish((Symbol::new(&env, "burn_address_set"),), burn_address);
+        Ok(())
+    }
+    
+    /// Get the burn address (defaults to the contract itself)
+    pub fn burn_address(env: Env) -> Address {
+        env.storage().instance()
+            .get(&DataKey::BurnAddress)
+            .unwrap_or_else(|| env.current_contract_address())
+    }
+    
+    /// Get the total amount of tokens burned
+    pub fn total_burned(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalBurned).unwrap_or(0)
     }
     
     /// Get balance
@@ -103,12 +272,12 @@ impl BlocklistToken {
     }
     
     /// Get token name
-    pub fn name(env: Env) -> Symbol {
+    pub fn name(env: Env) -> String {
         env.storage().instance().get(&DataKey::TokenName).unwrap()
     }
     
     /// Get token symbol
-    pub fn symbol(env: Env) -> Symbol {
+    pub fn symbol(env: Env) -> String {
         env.storage().instance().get(&DataKey::TokenSymbol).unwrap()
     }
     
@@ -121,16 +290,93 @@ impl BlocklistToken {
     pub fn total_supply(env: Env) -> i128 {
         env.storage().instance().get(&DataKey::TotalSupply).unwrap()
     }
+    
+    fn admin(env: &Env) -> Result<Address, TokenError> {
+        env.storage().instance().get(&DataKey::Admin).ok_or(TokenError::NotInitialized)
+    }
+    
+    /// Apply the blocklist checks, then move the balance. `trusted_pull` skips only the
+    /// sender check, for a trusted contract pulling through `transfer_from`.
+    fn transfer_checked(env: &Env, from: Address, to: Address, amount: i128, trusted_pull: bool) -> Result<(), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        // Check blocklist
+        if Self::blocked(env.clone(), from.clone()) && !trusted_pull {
+            return Err(TokenError::Blocked);
+        }
+        if Self::blocked(env.clone(), to.clone()) {
+            return Err(TokenError::Blocked);
+        }
+        
+        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
+        if from_balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        env.storage().instance().set(&DataKey::Balance(from.clone()), &from_balance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        // Read the recipient after the debit so a transfer to yourself nets to zero
+        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        env.storage().instance().set(&DataKey::Balance(to.clone()), &to_balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+        
+        env.events().publish((Symbol::new(env, "transfer"),), (from, to, amount));
+        Ok(())
+    }
+    
+    /// Apply the blocklist checks, then debit the tokens and remove them from supply. Nobody is
+    /// credited, so burned tokens can't be spent again; the burn address only labels the event.
+    fn burn_checked(env: &Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if Self::blocked(env.clone(), from.clone()) {
+            return Err(TokenError::Blocked);
+        }
+        
+        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
+        if from_balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        let burn_address = Self::burn_address(env.clone());
+        let total_supply: i128 = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
+        let total_burned = Self::total_burned(env.clone());
+        
+        env.storage().instance().set(&DataKey::Balance(from.clone()), &from_balance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        env.storage().instance().set(&DataKey::TotalSupply, &total_supply.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        env.storage().instance().set(&DataKey::TotalBurned, &total_burned.checked_add(amount).ok_or(TokenError::Overflow)?);
+        
+        env.events().publish((Symbol::new(env, "burn"),), (from, burn_address, amount));
+        Ok(())
+    }
+    
+    /// Deduct `amount` from the spender's allowance, keeping its expiration
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) -> Result<(), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        let (_, live_until_ledger): (i128, u32) = env.storage().instance().get(&key).unwrap_or((0, 0));
+        env.storage().instance().set(&key, &(allowance.checked_sub(amount).ok_or(TokenError::Overflow)?, live_until_ledger));
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use soroban_sdk::{testutils::Address as _, token, Address, Env};
 
     #[test]
     fn test_blocklist_functionality() {
         let env = Env::default();
+        env.mock_all_auths();
         let contract_id = env.register_contract(None, BlocklistToken);
         let client = BlocklistTokenClient::new(&env, &contract_id);
         
@@ -141,8 +387,8 @@ mod tests {
         // Initialize token
         client.initialize(
             &admin,
-            &Symbol::new(&env, "BlockToken"),
-            &Symbol::new(&env, "BLT"),
+            &String::from_str(&env, "BlockToken"),
+            &String::from_str(&env, "BLT"),
             &18,
             &1000000,
         );
@@ -158,8 +404,229 @@ mod tests {
         client.transfer(&admin, &user2, &1000);
         assert_eq!(client.balance(&user2), 1000);
         
+        // Blocked user1 cannot receive
+        let result = client.try_transfer(&user2, &user1, &100);
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+        
         // Unblock user1
         client.unblock_user(&user1);
         assert!(!client.blocked(&user1));
     }
+    
+    #[test]
+    fn test_burn_tracking() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, BlocklistToken);
+        let client = BlocklistTokenClient::new(&env, &contract_id);
+        
+        let admin = Address::generate(&env);
+        let burn_address = Address::generate(&env);
+        client.initialize(
+            &admin,
+            &String::from_str(&env, "BlockToken"),
+            &String::from_str(&env, "BLT"),
+            &18,
+            &1000000,
+        );
+        client.set_burn_address(&burn_address);
+        
+        client.burn(&admin, &1000);
+        client.burn(&admin, &2500);
+        
+        assert_eq!(client.total_burned(), 3500);
+        assert_eq!(client.total_supply(), 996500);
+        assert_eq!(client.balance(&admin), 996500);
+        assert_eq!(client.total_supply() + client.total_burned(), 1000000);
+        
+        // Burned tokens are gone rather than parked in a spendable balance
+        assert_eq!(client.balance(&burn_address), 0);
+    }
+    
+    fn setup_with_blocked_user(env: &Env) -> (BlocklistTokenClient<'_>, Address, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, BlocklistToken);
+        let client = BlocklistTokenClient::new(env, &contract_id);
+        
+        let admin = Address::generate(env);
+        let blocked_user = Address::generate(env);
+        client.initialize(
+            &admin,
+            &String::from_str(env, "BlockToken"),
+            &String::from_str(env, "BLT"),
+            &18,
+            &1000000,
+        );
+        client.block_user(&blocked_user);
+        (client, admin, blocked_user)
+    }
+    
+    /// Funds `blocked_user` before blocking them again, and has them approve `spender`
+    fn blocked_user_with_approval(client: &BlocklistTokenClient, admin: &Address, blocked_user: &Address, spender: &Address) {
+        client.unblock_user(blocked_user);
+        client.transfer(admin, blocked_user, &1000);
+        client.block_user(blocked_user);
+        client.approve(blocked_user, spender, &1000, &1000);
+    }
+    
+    #[test]
+    fn test_trusted_contract_pulls_from_blocked_user() {
+        let env = Env::default();
+        let (client, admin, blocked_user) = setup_with_blocked_user(&env);
+        let amm = Address::generate(&env);
+        client.add_trusted_contract(&amm);
+        blocked_user_with_approval(&client, &admin, &blocked_user, &amm);
+        
+        client.transfer_from(&amm, &blocked_user, &amm, &400);
+        assert_eq!(client.balance(&amm), 400);
+        assert_eq!(client.balance(&blocked_user), 600);
+    }
+    
+    #[test]
+    fn test_trusted_contract_cannot_send_to_blocked_user() {
+        let env = Env::default();
+        let (client, admin, blocked_user) = setup_with_blocked_user(&env);
+        let amm = Address::generate(&env);
+        client.add_trusted_contract(&amm);
+        client.transfer(&admin, &amm, &1000);
+        client.approve(&admin, &amm, &1000, &1000);
+        
+        // The bypass covers only the sender; the recipient check always applies
+        let result = client.try_transfer(&amm, &blocked_user, &400);
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+        let result = client.try_transfer_from(&amm, &admin, &blocked_user, &400);
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+        assert_eq!(client.balance(&blocked_user), 0);
+    }
+    
+    #[test]
+    fn test_untrusted_contract_hits_blocklist() {
+        let env = Env::default();
+        let (client, admin, blocked_user) = setup_with_blocked_user(&env);
+        let untrusted = Address::generate(&env);
+        blocked_user_with_approval(&client, &admin, &blocked_user, &untrusted);
+        
+        let result = client.try_transfer_from(&untrusted, &blocked_user, &untrusted, &400);
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+        let result = client.try_transfer(&blocked_user, &untrusted, &400);
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+    }
+    
+    #[test]
+    fn test_block_and_unblock_users_in_batch() {
+        let env = Env::default();
+        let (client, _admin, already_blocked) = setup_with_blocked_user(&env);
+        let (first, second) = (Address::generate(&env), Address::generate(&env));
+        
+        let users = Vec::from_array(&env, [first.clone(), already_blocked.clone(), second.clone()]);
+        client.block_users(&users);
+        assert!(client.blocked(&first));
+        assert!(client.blocked(&already_blocked));
+        assert!(client.blocked(&second));
+        
+        client.unblock_users(&Vec::from_array(&env, [first.clone(), second.clone()]));
+        assert!(!client.blocked(&first));
+        assert!(client.blocked(&already_blocked));
+        assert!(!client.blocked(&second));
+    }
+    
+    #[test]
+    fn test_mint_increases_supply() {
+        let env = Env::default();
+        let (client, _admin, _blocked_user) = setup_with_blocked_user(&env);
+        let recipient = Address::generate(&env);
+        
+        client.mint(&recipient, &500);
+        
+        assert_eq!(client.balance(&recipient), 500);
+        assert_eq!(client.total_supply(), 1000500);
+    }
+    
+    #[test]
+    fn test_mint_to_blocked_rejected() {
+        let env = Env::default();
+        let (client, _admin, blocked_user) = setup_with_blocked_user(&env);
+        
+        let result = client.try_mint(&blocked_user, &500);
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+    }
+    
+    #[test]
+    fn test_transfer_from_spends_allowance() {
+        let env = Env::default();
+        let (client, admin, _blocked_user) = setup_with_blocked_user(&env);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        
+        client.approve(&admin, &spender, &300, &1000);
+        client.transfer_from(&spender, &admin, &recipient, &200);
+        
+        assert_eq!(client.allowance(&admin, &spender), 100);
+        assert_eq!(client.balance(&recipient), 200);
+    }
+    
+    #[test]
+    fn test_negative_transfer_from_rejected() {
+        let env = Env::default();
+        let (client, admin, _blocked_user) = setup_with_blocked_user(&env);
+        let spender = Address::generate(&env);
+        let victim = Address::generate(&env);
+        client.transfer(&admin, &victim, &500);
+        
+        // A negative amount would pull the victim's funds back to `from` and grow the allowance
+        let result = client.try_transfer_from(&spender, &admin, &victim, &-500);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+        let result = client.try_transfer(&admin, &victim, &-500);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+        
+        assert_eq!(client.balance(&victim), 500);
+        assert_eq!(client.allowance(&admin, &spender), 0);
+    }
+    
+    #[test]
+    fn test_self_transfer_keeps_balance() {
+        let env = Env::default();
+        let (client, admin, _blocked_user) = setup_with_blocked_user(&env);
+        
+        client.transfer(&admin, &admin, &1000);
+        assert_eq!(client.balance(&admin), 1000000);
+    }
+    
+    #[test]
+    fn test_blocked_spender_cannot_use_allowance() {
+        let env = Env::default();
+        let (client, admin, _blocked_user) = setup_with_blocked_user(&env);
+        let spender = Address::generate(&env);
+        
+        client.approve(&admin, &spender, &300, &1000);
+        client.block_user(&spender);
+        let result = client.try_transfer_from(&spender, &admin, &Address::generate(&env), &200);
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+    }
+    
+    #[test]
+    fn test_sep41_interface_via_token_client() {
+        let env = Env::default();
+        let (client, admin, _blocked_user) = setup_with_blocked_user(&env);
+        let token = token::Client::new(&env, &client.address);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        
+        assert_eq!(token.name(), String::from_str(&env, "BlockToken"));
+        assert_eq!(token.symbol(), String::from_str(&env, "BLT"));
+        assert_eq!(token.decimals(), 18);
+        
+        token.approve(&admin, &spender, &300, &1000);
+        assert_eq!(token.allowance(&admin, &spender), 300);
+        
+        token.transfer(&admin, &recipient, &100);
+        token.transfer_from(&spender, &admin, &recipient, &100);
+        token.burn(&recipient, &50);
+        token.burn_from(&spender, &admin, &200);
+        
+        assert_eq!(token.allowance(&admin, &spender), 0);
+        assert_eq!(token.balance(&recipient), 150);
+        assert_eq!(token.balance(&admin), 999600);
+        assert_eq!(client.total_burned(), 250);
+    }
 }
@@ -1,12 +1,12 @@
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, vec, Env, Symbol, Vec};
 
 #[contract]
 pub struct HelloContract;
 
 #[contractimpl]
 impl HelloContract {
-    pub fn hello(env: Env, to: Symbol) -> Symbol {
-        symbol_short!("Hello")
+    pub fn hello(env: Env, to: Symbol) -> Vec<Symbol> {
+        vec![&env, symbol_short!("Hello"), to]
     }
 }
 
@@ -20,3 +20,18 @@ pub struct State {
     pub count: u32,
     pub message: Symbol,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hello_echoes_name() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, HelloContract);
+        let client = HelloContractClient::new(&env, &contract_id);
+
+        let greeting = client.hello(&symbol_short!("Dev"));
+        assert_eq!(greeting, vec![&env, symbol_short!("Hello"), symbol_short!("Dev")]);
+    }
+}
@@ -1,10 +1,13 @@
 #![no_std]
 
-/// Test contracts for the Stellar Smart Contracts LSP extension
-/// 
-/// This module contains various example contracts to test and demonstrate
-/// the LSP features including syntax highlighting, completions, diagnostics,
-/// and hover information.
+//! Test contracts for the Stellar Smart Contracts LSP extension
+//!
+//! This module contains various example contracts to test and demonstrate
+//! the LSP features including syntax highlighting, completions, diagnostics,
+//! and hover information.
+
+// Shared error types
+mod token_error;
 
 // Basic contracts
 mod hello_world;
@@ -24,18 +27,19 @@ mod advanced_marketplace;
 mod lsp_test;
 
 // Re-export specific contract structs and clients to avoid ambiguous glob re-exports
+pub use token_error::TokenError;
 pub use hello_world::HelloContract;
-pub use token_contract::{TokenContract, TokenMetadata};
-pub use voting_contract::VotingContract;
+pub use token_contract::{TokenContract, TokenMetadata, TokenEvent};
+pub use voting_contract::{VotingContract, ProposalOutcome, Vote, VoteRecord, VotingError, VotingEvent};
 
 // Advanced contracts
-pub use allowlist_token::{AllowlistToken, AllowlistTokenClient, AllowlistEvent};
+pub use allowlist_token::{AllowlistToken, AllowlistTokenClient, AllowlistEvent, AllowlistError};
 pub use blocklist_token::{BlocklistToken, BlocklistTokenClient, BlocklistEvent};
 pub use pausable_token::{PausableToken, PausableTokenClient, PausableEvent};
 pub use ownable_counter::{OwnableCounter, OwnableCounterClient, OwnableEvent};
 
 // Access Control NFT, Multi-Extension Token, and Advanced Marketplace
-pub use access_control_nft::{AccessControlNFT, AccessControlNFTClient, AccessControlEvent};
+pub use access_control_nft::{AccessControlNFT, AccessControlNFTClient, AccessControlEvent, PublicSale};
 pub use multi_extension_token::{MultiExtensionToken, MultiExtensionTokenClient};
 pub use advanced_marketplace::{AdvancedMarketplace, AdvancedMarketplaceClient, Listing, ListingStatus, MarketplaceEvent};
 
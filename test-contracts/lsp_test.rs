@@ -142,6 +142,24 @@ impl LspTest {
         env.events().publish((Symbol::new(&env, "data_stored"),), (id, data));
     }
     
+    /// Test function for owner-gated configuration
+    pub fn set_config(env: Env, key: Symbol, value: i128) {
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+        
+        Self::write_config(&env, key, value);
+    }
+    
+    /// Test function for batched configuration updates
+    pub fn set_configs(env: Env, entries: Vec<(Symbol, i128)>) {
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+        
+        for (key, value) in entries.iter() {
+            Self::write_config(&env, key, value);
+        }
+    }
+    
     /// Test function with complex return types
     pub fn get_user_info(env: Env, user: Address) -> Map<Symbol, i128> {
         let mut result = Map::new(&env);
@@ -221,22 +239,27 @@ impl LspTest {
     pub fn get_config(env: Env, key: Symbol) -> Option<i128> {
         env.storage().instance().get(&DataKey::Config(key))
     }
+    
+    fn write_config(env: &Env, key: Symbol, value: i128) {
+        env.storage().instance().set(&DataKey::Config(key.clone()), &value);
+        env.events().publish((Symbol::new(env, "config_changed"),), (key, value));
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env, Vec, Bytes};
+    use soroban_sdk::{testutils::{Address as _, MockAuth, MockAuthInvoke}, Address, Env, IntoVal, Vec, Bytes};
 
     #[test]
     fn test_lsp_contract_functionality() {
         let env = Env::default();
-        let contract_id = env.register_contract(None, LSPTestContract);
-        let client = LSPTestContractClient::new(&env, &contract_id);
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, LspTest);
+        let client = LspTestClient::new(&env, &contract_id);
         
         let owner = Address::generate(&env);
         let user1 = Address::generate(&env);
-        let user2 = Address::generate(&env);
         
         // Initialize contract
         client.initialize(&owner);
@@ -266,17 +289,82 @@ mod tests {
         client.store_test_data(&123, &test_data);
         assert_eq!(client.get_test_data(&123), Some(test_data));
         
-        // Test bulk transfer
-        let recipients = Vec::from_array(&env, [user1.clone(), user2.clone()]);
-        let amounts = Vec::from_array(&env, [100i128, 200i128]);
-        
-        // First set balance for owner
-        // This would normally be done through mint or initial setup
-        // For test purposes, we'll just verify the function structure
-        
         // Test user info
         let user_info = client.get_user_info(&owner);
         assert_eq!(user_info.get(Symbol::new(&env, "balance")), Some(0));
         assert_eq!(user_info.get(Symbol::new(&env, "is_admin")), Some(1));
     }
+    
+    #[test]
+    fn test_bulk_transfer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, LspTest);
+        let client = LspTestClient::new(&env, &contract_id);
+        
+        let owner = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        client.initialize(&owner);
+        
+        // The contract has no mint, so seed the owner's balance directly
+        env.as_contract(&contract_id, || {
+            env.storage().instance().set(&DataKey::Balances(owner.clone()), &1000i128);
+        });
+        assert_eq!(client.balance(&owner), 1000);
+        
+        let recipients = Vec::from_array(&env, [user1.clone(), user2.clone()]);
+        let amounts = Vec::from_array(&env, [100i128, 200i128]);
+        client.bulk_transfer(&owner, &recipients, &amounts);
+        
+        assert_eq!(client.balance(&owner), 700);
+        assert_eq!(client.balance(&user1), 100);
+        assert_eq!(client.balance(&user2), 200);
+    }
+    
+    #[test]
+    fn test_set_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, LspTest);
+        let client = LspTestClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+        
+        let fee = Symbol::new(&env, "fee");
+        let limit = Symbol::new(&env, "limit");
+        assert_eq!(client.get_config(&fee), None);
+        
+        client.set_config(&fee, &25);
+        assert_eq!(client.get_config(&fee), Some(25));
+        
+        let entries = Vec::from_array(&env, [(fee.clone(), 30i128), (limit.clone(), 1000i128)]);
+        client.set_configs(&entries);
+        assert_eq!(client.get_config(&fee), Some(30));
+        assert_eq!(client.get_config(&limit), Some(1000));
+    }
+    
+    #[test]
+    fn test_set_config_rejects_non_owner() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, LspTest);
+        let client = LspTestClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+        
+        let fee = Symbol::new(&env, "fee");
+        let outsider = Address::generate(&env);
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &outsider,
+                invoke: &MockAuthInvoke {
+                    contract: &contract_id,
+                    fn_name: "set_config",
+                    args: (fee.clone(), 25i128).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_set_config(&fee, &25);
+        
+        assert!(result.is_err());
+        assert_eq!(client.get_config(&fee), None);
+    }
 }
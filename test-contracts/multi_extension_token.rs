@@ -1,21 +1,25 @@
 // Simplified multi-extension token contract demonstrating multiple patterns
 // Uses basic Soroban SDK features without OpenZeppelin dependencies
-use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, IntoVal, String, Symbol, Val, Vec};
+use crate::token_error::TokenError;
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
+    // Per-account entries, kept in persistent storage
     Balance(Address),
-    Allowance(Address, Address),
+    Allowance(Address, Address), // (amount, live_until_ledger)
     Admin,
-    Roles(Address, Symbol), // (address, role)
+    Roles(Address, Symbol), // (address, role) -> expiry timestamp
     Allowlist(Address),
     Blocklist(Address),
+    // Global config, kept in instance storage
     Paused,
     TokenName,
     TokenSymbol,
     TokenDecimals,
     TotalSupply,
+    InitVersion,
 }
 
 #[contracttype]
@@ -30,6 +34,22 @@ pub enum MultiExtensionEvent {
     Unpaused,
 }
 
+/// Maximum number of entries in a single batch call
+const MAX_BATCH_SIZE: u32 = 50;
+
+/// Role expiry that never lapses
+const PERMANENT_ROLE: u64 = u64::MAX;
+
+/// Initialization version at which the token metadata is locked for good
+const FINAL_INIT_VERSION: u32 = 3;
+
+/// Roughly one day of ledgers at a 5 second close time
+const DAY_IN_LEDGERS: u32 = 17280;
+
+/// Per-account entries (balances, allowances, roles, allow/blocklist) live 30 days past their last write
+const ACCOUNT_TTL_EXTEND: u32 = 30 * DAY_IN_LEDGERS;
+const ACCOUNT_TTL_THRESHOLD: u32 = ACCOUNT_TTL_EXTEND - DAY_IN_LEDGERS;
+
 #[contract]
 pub struct MultiExtensionToken;
 
@@ -39,13 +59,13 @@ impl MultiExtensionToken {
     pub fn initialize(
         env: Env,
         admin: Address,
-        name: Symbol,
-        symbol: Symbol,
+        name: String,
+        symbol: String,
         decimals: u32,
         total_supply: i128,
-    ) {
+    ) -> Result<(), TokenError> {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            return Err(TokenError::AlreadyInitialized);
         }
         
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -53,7 +73,7 @@ impl MultiExtensionToken {
         env.storage().instance().set(&DataKey::TokenSymbol, &symbol);
         env.storage().instance().set(&DataKey::TokenDecimals, &decimals);
         env.storage().instance().set(&DataKey::TotalSupply, &total_supply);
-        env.storage().instance().set(&DataKey::Balance(admin.clone()), &total_supply);
+        Self::set_persistent(&env, &DataKey::Balance(admin.clone()), &total_supply);
         env.storage().instance().set(&DataKey::Paused, &false);
         
         // Admin gets all roles by default
@@ -61,87 +81,136 @@ impl MultiExtensionToken {
         let minter_role = Symbol::new(&env, "minter");
         let pauser_role = Symbol::new(&env, "pauser");
         
-        env.storage().instance().set(&DataKey::Roles(admin.clone(), admin_role), &true);
-        env.storage().instance().set(&DataKey::Roles(admin.clone(), minter_role), &true);
-        env.storage().instance().set(&DataKey::Roles(admin.clone(), pauser_role), &true);
+        Self::set_persistent(&env, &DataKey::Roles(admin.clone(), admin_role), &PERMANENT_ROLE);
+        Self::set_persistent(&env, &DataKey::Roles(admin.clone(), minter_role), &PERMANENT_ROLE);
+        Self::set_persistent(&env, &DataKey::Roles(admin.clone(), pauser_role), &PERMANENT_ROLE);
         
         // Admin is automatically allowed
-        env.storage().instance().set(&DataKey::Allowlist(admin.clone()), &true);
+        Self::set_persistent(&env, &DataKey::Allowlist(admin.clone()), &true);
+        env.storage().instance().set(&DataKey::InitVersion, &1u32);
+        Ok(())
+    }
+    
+    /// Redo the metadata setup at a higher `version` (admin only), until the final version locks it
+    pub fn reinitialize(env: Env, version: u32, name: String, symbol: String, decimals: u32) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        let current = Self::init_version(env.clone());
+        if current >= FINAL_INIT_VERSION {
+            return Err(TokenError::InitializationLocked);
+        }
+        if version <= current || version > FINAL_INIT_VERSION {
+            return Err(TokenError::InvalidInitVersion);
+        }
+        
+        env.storage().instance().set(&DataKey::TokenName, &name);
+        env.storage().instance().set(&DataKey::TokenSymbol, &symbol);
+        env.storage().instance().set(&DataKey::TokenDecimals, &decimals);
+        env.storage().instance().set(&DataKey::InitVersion, &version);
+        env.events().publish((Symbol::new(&env, "reinitialized"),), version);
+        Ok(())
+    }
+    
+    /// Lock initialization at the final version (admin only)
+    pub fn lock_initialization(env: Env) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::InitVersion, &FINAL_INIT_VERSION);
+        Ok(())
+    }
+    
+    /// Get the current initialization version (0 before `initialize`)
+    pub fn init_version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::InitVersion).unwrap_or(0)
     }
     
     // === Access Control Functions ===
     
-    /// Check if address has a specific role
+    /// Check if address has a specific role that has not yet expired
     pub fn has_role(env: Env, account: Address, role: Symbol) -> bool {
-        env.storage().instance().get(&DataKey::Roles(account, role)).unwrap_or(false)
+        Self::role_expiry(env.clone(), account, role)
+            .is_some_and(|expires_at| env.ledger().timestamp() <= expires_at)
+    }
+    
+    /// Get the expiry timestamp of a granted role
+    pub fn role_expiry(env: Env, account: Address, role: Symbol) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::Roles(account, role))
     }
     
-    /// Grant a role to an address (admin only)
-    pub fn grant_role(env: Env, account: Address, role: Symbol) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    /// Grant a role to an address until `expires_at` (admin only); `u64::MAX` is permanent
+    pub fn grant_role(env: Env, account: Address, role: Symbol, expires_at: u64) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Roles(account.clone(), role.clone()), &true);
-        env.events().publish((Symbol::new(&env, "role_granted"),), (account, role));
+        Self::set_persistent(&env, &DataKey::Roles(account.clone(), role.clone()), &expires_at);
+        env.events().publish((Symbol::new(&env, "role_granted"),), (account, role, expires_at));
+        Ok(())
     }
     
     /// Revoke a role from an address (admin only)
-    pub fn revoke_role(env: Env, account: Address, role: Symbol) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn revoke_role(env: Env, account: Address, role: Symbol) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Roles(account.clone(), role.clone()), &false);
+        env.storage().persistent().remove(&DataKey::Roles(account.clone(), role.clone()));
         env.events().publish((Symbol::new(&env, "role_revoked"),), (account, role));
+        Ok(())
     }
     
     // === Allowlist Functions ===
     
     /// Check if user is in allowlist
     pub fn allowed(env: Env, user: Address) -> bool {
-        env.storage().instance().get(&DataKey::Allowlist(user)).unwrap_or(false)
+        env.storage().persistent().get(&DataKey::Allowlist(user)).unwrap_or(false)
     }
     
     /// Add user to allowlist (admin only)
-    pub fn allow_user(env: Env, user: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn allow_user(env: Env, user: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Allowlist(user.clone()), &true);
+        Self::set_persistent(&env, &DataKey::Allowlist(user.clone()), &true);
         env.events().publish((Symbol::new(&env, "user_allowed"),), user);
+        Ok(())
     }
     
     /// Remove user from allowlist (admin only)
-    pub fn disallow_user(env: Env, user: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn disallow_user(env: Env, user: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Allowlist(user.clone()), &false);
+        Self::set_persistent(&env, &DataKey::Allowlist(user.clone()), &false);
         env.events().publish((Symbol::new(&env, "user_disallowed"),), user);
+        Ok(())
     }
     
     // === Blocklist Functions ===
     
     /// Check if user is blocked
     pub fn blocked(env: Env, user: Address) -> bool {
-        env.storage().instance().get(&DataKey::Blocklist(user)).unwrap_or(false)
+        env.storage().persistent().get(&DataKey::Blocklist(user)).unwrap_or(false)
     }
     
     /// Add user to blocklist (admin only)
-    pub fn block_user(env: Env, user: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn block_user(env: Env, user: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Blocklist(user.clone()), &true);
+        Self::set_persistent(&env, &DataKey::Blocklist(user.clone()), &true);
         env.events().publish((Symbol::new(&env, "user_blocked"),), user);
+        Ok(())
     }
     
     /// Remove user from blocklist (admin only)
-    pub fn unblock_user(env: Env, user: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn unblock_user(env: Env, user: Address) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Blocklist(user.clone()), &false);
+        Self::set_persistent(&env, &DataKey::Blocklist(user.clone()), &false);
         env.events().publish((Symbol::new(&env, "user_unblocked"),), user);
+        Ok(())
     }
     
     // === Pausable Functions ===
@@ -152,133 +221,192 @@ impl MultiExtensionToken {
     }
     
     /// Pause the contract (pauser role required)
-    pub fn pause(env: Env) {
+    pub fn pause(env: Env, caller: Address) -> Result<(), TokenError> {
+        caller.require_auth();
         let pauser_role = Symbol::new(&env, "pauser");
-        let caller = env.current_contract_address(); // In real implementation, this would be msg.sender
         
         if !Self::has_role(env.clone(), caller, pauser_role) {
-            panic!("Caller does not have pauser role");
+            return Err(TokenError::Unauthorized);
         }
         
         if Self::paused(env.clone()) {
-            panic!("Contract already paused");
+            return Err(TokenError::AlreadyPaused);
         }
         
         env.storage().instance().set(&DataKey::Paused, &true);
         env.events().publish((Symbol::new(&env, "paused"),), ());
+        Ok(())
     }
     
     /// Unpause the contract (pauser role required)
-    pub fn unpause(env: Env) {
+    pub fn unpause(env: Env, caller: Address) -> Result<(), TokenError> {
+        caller.require_auth();
         let pauser_role = Symbol::new(&env, "pauser");
-        let caller = env.current_contract_address(); // In real implementation, this would be msg.sender
         
         if !Self::has_role(env.clone(), caller, pauser_role) {
-            panic!("Caller does not have pauser role");
+            return Err(TokenError::Unauthorized);
         }
         
         if !Self::paused(env.clone()) {
-            panic!("Contract not paused");
+            return Err(TokenError::NotPaused);
         }
         
         env.storage().instance().set(&DataKey::Paused, &false);
         env.events().publish((Symbol::new(&env, "unpaused"),), ());
+        Ok(())
     }
     
     // === Token Functions ===
     
     /// Transfer tokens with all security checks
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
         from.require_auth();
         
-        // Check if contract is paused
-        if Self::paused(env.clone()) {
-            panic!("Contract is paused");
-        }
+        Self::transfer_checked(&env, from, to, amount)
+    }
+    
+    /// Transfer to many recipients at once. Every recipient is checked against the
+    /// pause, allowlist, and blocklist rules before any balance moves.
+    pub fn batch_transfer(env: Env, from: Address, recipients: Vec<Address>, amounts: Vec<i128>) -> Result<(), TokenError> {
+        from.require_auth();
         
-        // Check allowlist
-        if !Self::allowed(env.clone(), from.clone()) {
-            panic!("From address not allowed");
+        if recipients.len() != amounts.len() {
+            return Err(TokenError::LengthMismatch);
         }
-        if !Self::allowed(env.clone(), to.clone()) {
-            panic!("To address not allowed");
+        if recipients.len() > MAX_BATCH_SIZE {
+            return Err(TokenError::BatchTooLarge);
         }
         
-        // Check blocklist
-        if Self::blocked(env.clone(), from.clone()) {
-            panic!("From address is blocked");
+        let mut total_amount = 0i128;
+        for amount in amounts.iter() {
+            if amount < 0 {
+                return Err(TokenError::InvalidAmount);
+            }
+            total_amount = total_amount.checked_add(amount).ok_or(TokenError::Overflow)?;
         }
-        if Self::blocked(env.clone(), to.clone()) {
-            panic!("To address is blocked");
+        
+        for recipient in recipients.iter() {
+            if let Some(error) = Self::transfer_error(&env, &from, &recipient, total_amount) {
+                return Err(error);
+            }
         }
         
-        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
-        if from_balance < amount {
-            panic!("Insufficient balance");
+        let from_balance = Self::balance(env.clone(), from.clone());
+        Self::set_persistent(&env, &DataKey::Balance(from.clone()), &from_balance.checked_sub(total_amount).ok_or(TokenError::Overflow)?);
+        
+        for (recipient, amount) in recipients.iter().zip(amounts.iter()) {
+            let to_balance = Self::balance(env.clone(), recipient.clone());
+            Self::set_persistent(&env, &DataKey::Balance(recipient.clone()), &to_balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+            env.events().publish((Symbol::new(&env, "transfer"),), (from.clone(), recipient, amount));
+        }
+        Ok(())
+    }
+    
+    /// Approve a spender to transfer tokens on behalf of `from` until `live_until_ledger`
+    pub fn approve(
+        env: Env,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        live_until_ledger: u32,
+    ) -> Result<(), TokenError> {
+        from.require_auth();
+        
+        Self::write_allowance(&env, &from, spender, amount, live_until_ledger)
+    }
+    
+    /// Approve several spenders at once on behalf of `from`, all expiring at `live_until_ledger`
+    pub fn approve_many(
+        env: Env,
+        from: Address,
+        approvals: Vec<(Address, i128)>,
+        live_until_ledger: u32,
+    ) -> Result<(), TokenError> {
+        from.require_auth();
+        
+        if approvals.len() > MAX_BATCH_SIZE {
+            return Err(TokenError::BatchTooLarge);
         }
         
-        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        for (spender, amount) in approvals.iter() {
+            Self::write_allowance(&env, &from, spender, amount, live_until_ledger)?;
+        }
+        Ok(())
+    }
+    
+    /// Get the amount a spender may transfer on behalf of `from` (0 once the approval has expired)
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        let (amount, live_until_ledger): (i128, u32) = env.storage().persistent()
+            .get(&DataKey::Allowance(from, spender))
+            .unwrap_or((0, 0));
         
-        env.storage().instance().set(&DataKey::Balance(from.clone()), &(from_balance - amount));
-        env.storage().instance().set(&DataKey::Balance(to.clone()), &(to_balance + amount));
+        if live_until_ledger < env.ledger().sequence() {
+            0
+        } else {
+            amount
+        }
+    }
+    
+    /// Transfer tokens on behalf of `from` using the spender's allowance
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        spender.require_auth();
         
-        env.events().publish((Symbol::new(&env, "transfer"),), (from, to, amount));
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        Self::transfer_checked(&env, from, to, amount)
     }
     
     /// Mint tokens (minter role required)
-    pub fn mint(env: Env, to: Address, amount: i128) {
+    pub fn mint(env: Env, minter: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        minter.require_auth();
         let minter_role = Symbol::new(&env, "minter");
-        let caller = env.current_contract_address(); // In real implementation, this would be msg.sender
         
-        if !Self::has_role(env.clone(), caller, minter_role) {
-            panic!("Caller does not have minter role");
+        if !Self::has_role(env.clone(), minter, minter_role) {
+            return Err(TokenError::Unauthorized);
         }
         
         if Self::paused(env.clone()) {
-            panic!("Contract is paused");
+            return Err(TokenError::Paused);
+        }
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
         }
         
-        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        let to_balance: i128 = env.storage().persistent().get(&DataKey::Balance(to.clone())).unwrap_or(0);
         let total_supply: i128 = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
         
-        env.storage().instance().set(&DataKey::Balance(to.clone()), &(to_balance + amount));
-        env.storage().instance().set(&DataKey::TotalSupply, &(total_supply + amount));
+        Self::set_persistent(&env, &DataKey::Balance(to.clone()), &to_balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+        env.storage().instance().set(&DataKey::TotalSupply, &total_supply.checked_add(amount).ok_or(TokenError::Overflow)?);
         
         env.events().publish((Symbol::new(&env, "mint"),), (to, amount));
+        Ok(())
     }
     
-    /// Burn tokens (from own balance or with allowance)
-    pub fn burn(env: Env, from: Address, amount: i128) {
+    /// Burn tokens from `from`'s own balance
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), TokenError> {
         from.require_auth();
         
-        if Self::paused(env.clone()) {
-            panic!("Contract is paused");
-        }
-        
-        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
-        if from_balance < amount {
-            panic!("Insufficient balance to burn");
-        }
-        
-        let total_supply: i128 = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
-        
-        env.storage().instance().set(&DataKey::Balance(from.clone()), &(from_balance - amount));
-        env.storage().instance().set(&DataKey::TotalSupply, &(total_supply - amount));
+        Self::burn_checked(&env, from, amount)
+    }
+    
+    /// Burn tokens on behalf of `from` using the spender's allowance
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) -> Result<(), TokenError> {
+        spender.require_auth();
         
-        env.events().publish((Symbol::new(&env, "burn"),), (from, amount));
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        Self::burn_checked(&env, from, amount)
     }
     
     // === View Functions ===
     
     pub fn balance(env: Env, account: Address) -> i128 {
-        env.storage().instance().get(&DataKey::Balance(account)).unwrap_or(0)
+        env.storage().persistent().get(&DataKey::Balance(account)).unwrap_or(0)
     }
     
-    pub fn name(env: Env) -> Symbol {
+    pub fn name(env: Env) -> String {
         env.storage().instance().get(&DataKey::TokenName).unwrap()
     }
     
-    pub fn symbol(env: Env) -> Symbol {
+    pub fn symbol(env: Env) -> String {
         env.storage().instance().get(&DataKey::TokenSymbol).unwrap()
     }
     
@@ -289,16 +417,167 @@ impl MultiExtensionToken {
     pub fn total_supply(env: Env) -> i128 {
         env.storage().instance().get(&DataKey::TotalSupply).unwrap()
     }
+    
+    // === Simulation Functions ===
+    
+    /// Check whether a transfer would pass every security check, without mutating state
+    pub fn can_transfer(env: Env, from: Address, to: Address, amount: i128) -> bool {
+        Self::transfer_error(&env, &from, &to, amount).is_none()
+    }
+    
+    /// Preview the (from, to) balances after a transfer; unchanged if the transfer would fail
+    pub fn transfer_preview(env: Env, from: Address, to: Address, amount: i128) -> (i128, i128) {
+        Self::balances_after_transfer(&env, &from, &to, amount).unwrap_or_else(|_| {
+            (Self::balance(env.clone(), from), Self::balance(env.clone(), to))
+        })
+    }
+    
+    /// Run the transfer checks and compute the (from, to) balances a transfer would leave;
+    /// a transfer to yourself leaves the balance unchanged
+    fn balances_after_transfer(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(i128, i128), TokenError> {
+        if let Some(error) = Self::transfer_error(env, from, to, amount) {
+            return Err(error);
+        }
+        
+        let from_after = Self::balance(env.clone(), from.clone()).checked_sub(amount).ok_or(TokenError::Overflow)?;
+        if from == to {
+            return Ok((from_after + amount, from_after + amount));
+        }
+        let to_after = Self::balance(env.clone(), to.clone()).checked_add(amount).ok_or(TokenError::Overflow)?;
+        Ok((from_after, to_after))
+    }
+    
+    /// Reason a transfer would be rejected by the pause, amount, allowlist, blocklist, or balance checks
+    fn transfer_error(env: &Env, from: &Address, to: &Address, amount: i128) -> Option<TokenError> {
+        // Check if contract is paused
+        if Self::paused(env.clone()) {
+            return Some(TokenError::Paused);
+        }
+        if amount <= 0 {
+            return Some(TokenError::InvalidAmount);
+        }
+        
+        // Check allowlist
+        if !Self::allowed(env.clone(), from.clone()) {
+            return Some(TokenError::NotAllowed);
+        }
+        if !Self::allowed(env.clone(), to.clone()) {
+            return Some(TokenError::NotAllowed);
+        }
+        
+        // Check blocklist
+        if Self::blocked(env.clone(), from.clone()) {
+            return Some(TokenError::Blocked);
+        }
+        if Self::blocked(env.clone(), to.clone()) {
+            return Some(TokenError::Blocked);
+        }
+        
+        if Self::balance(env.clone(), from.clone()) < amount {
+            return Some(TokenError::InsufficientBalance);
+        }
+        None
+    }
+    
+    fn admin(env: &Env) -> Result<Address, TokenError> {
+        env.storage().instance().get(&DataKey::Admin).ok_or(TokenError::NotInitialized)
+    }
+    
+    /// Write a per-account entry to persistent storage and extend its TTL, along with the
+    /// contract instance that holds the global config
+    fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, ACCOUNT_TTL_THRESHOLD, ACCOUNT_TTL_EXTEND);
+        env.storage().instance().extend_ttl(ACCOUNT_TTL_THRESHOLD, ACCOUNT_TTL_EXTEND);
+    }
+    
+    /// Apply pause, allowlist, and blocklist checks, then move the balance
+    fn transfer_checked(env: &Env, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        let (from_after, to_after) = Self::balances_after_transfer(env, &from, &to, amount)?;
+        
+        Self::set_persistent(env, &DataKey::Balance(from.clone()), &from_after);
+        Self::set_persistent(env, &DataKey::Balance(to.clone()), &to_after);
+        
+        env.events().publish((Symbol::new(env, "transfer"),), (from, to, amount));
+        Ok(())
+    }
+    
+    /// Apply the pause, amount, and blocklist checks, then remove the tokens from the balance and total supply
+    fn burn_checked(env: &Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        if Self::paused(env.clone()) {
+            return Err(TokenError::Paused);
+        }
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if Self::blocked(env.clone(), from.clone()) {
+            return Err(TokenError::Blocked);
+        }
+        
+        let from_balance: i128 = env.storage().persistent().get(&DataKey::Balance(from.clone())).unwrap_or(0);
+        if from_balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        let total_supply: i128 = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
+        
+        Self::set_persistent(env, &DataKey::Balance(from.clone()), &from_balance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        env.storage().instance().set(&DataKey::TotalSupply, &total_supply.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        
+        env.events().publish((Symbol::new(env, "burn"),), (from, amount));
+        Ok(())
+    }
+    
+    /// Validate and store an approval, emitting the approve event
+    fn write_allowance(
+        env: &Env,
+        from: &Address,
+        spender: Address,
+        amount: i128,
+        live_until_ledger: u32,
+    ) -> Result<(), TokenError> {
+        if amount < 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if amount > 0 && live_until_ledger < env.ledger().sequence() {
+            return Err(TokenError::InvalidExpiration);
+        }
+        
+        Self::set_persistent(env, &DataKey::Allowance(from.clone(), spender.clone()), &(amount, live_until_ledger));
+        env.events().publish((Symbol::new(env, "approve"),), (from.clone(), spender, amount, live_until_ledger));
+        Ok(())
+    }
+    
+    /// Deduct `amount` from the spender's allowance, keeping its expiration
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) -> Result<(), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        // An i128::MAX allowance is treated as infinite and never decremented
+        if allowance != i128::MAX {
+            let key = DataKey::Allowance(from.clone(), spender.clone());
+            let (_, live_until_ledger): (i128, u32) = env.storage().persistent().get(&key).unwrap_or((0, 0));
+            Self::set_persistent(env, &key, &(allowance.checked_sub(amount).ok_or(TokenError::Overflow)?, live_until_ledger));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env, Vec};
 
     #[test]
     fn test_multi_extension_token() {
         let env = Env::default();
+        env.mock_all_auths();
         let contract_id = env.register_contract(None, MultiExtensionToken);
         let client = MultiExtensionTokenClient::new(&env, &contract_id);
         
@@ -309,8 +588,8 @@ mod tests {
         // Initialize token
         client.initialize(
             &admin,
-            &Symbol::new(&env, "MultiToken"),
-            &Symbol::new(&env, "MT"),
+            &String::from_str(&env, "MultiToken"),
+            &String::from_str(&env, "MT"),
             &18,
             &1000000,
         );
@@ -328,10 +607,10 @@ mod tests {
         
         // Test pausable
         assert!(!client.paused());
-        client.pause();
+        client.pause(&admin);
         assert!(client.paused());
         
-        client.unpause();
+        client.unpause(&admin);
         assert!(!client.paused());
         
         // Test multi-pattern transfer
@@ -339,4 +618,419 @@ mod tests {
         client.transfer(&admin, &user1, &1000);
         assert_eq!(client.balance(&user1), 1000);
     }
+    
+    #[test]
+    fn test_max_allowance_not_decremented() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, MultiExtensionToken);
+        let client = MultiExtensionTokenClient::new(&env, &contract_id);
+        
+        let admin = Address::generate(&env);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.initialize(
+            &admin,
+            &String::from_str(&env, "MultiToken"),
+            &String::from_str(&env, "MT"),
+            &18,
+            &1000000,
+        );
+        client.allow_user(&recipient);
+        
+        client.approve(&admin, &spender, &i128::MAX, &1000);
+        client.transfer_from(&spender, &admin, &recipient, &100);
+        client.transfer_from(&spender, &admin, &recipient, &200);
+        
+        assert_eq!(client.allowance(&admin, &spender), i128::MAX);
+        assert_eq!(client.balance(&recipient), 300);
+    }
+    
+    #[test]
+    fn test_approve_many() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, MultiExtensionToken);
+        let client = MultiExtensionTokenClient::new(&env, &contract_id);
+        
+        let admin = Address::generate(&env);
+        client.initialize(
+            &admin,
+            &String::from_str(&env, "MultiToken"),
+            &String::from_str(&env, "MT"),
+            &18,
+            &1000000,
+        );
+        
+        let spender1 = Address::generate(&env);
+        let spender2 = Address::generate(&env);
+        let spender3 = Address::generate(&env);
+        let approvals = Vec::from_array(&env, [
+            (spender1.clone(), 100i128),
+            (spender2.clone(), 200i128),
+            (spender3.clone(), 300i128),
+        ]);
+        client.approve_many(&admin, &approvals, &1000);
+        
+        assert_eq!(client.allowance(&admin, &spender1), 100);
+        assert_eq!(client.allowance(&admin, &spender2), 200);
+        assert_eq!(client.allowance(&admin, &spender3), 300);
+    }
+    
+    fn setup(env: &Env) -> MultiExtensionTokenClient<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, MultiExtensionToken);
+        let client = MultiExtensionTokenClient::new(env, &contract_id);
+        client.initialize(
+            &Address::generate(env),
+            &String::from_str(env, "MultiToken"),
+            &String::from_str(env, "MT"),
+            &18,
+            &1000000,
+        );
+        client
+    }
+    
+    #[test]
+    fn test_reinitialize_before_lock() {
+        let env = Env::default();
+        let client = setup(&env);
+        assert_eq!(client.init_version(), 1);
+        
+        client.reinitialize(&2, &String::from_str(&env, "FixedToken"), &String::from_str(&env, "FT"), &7);
+        assert_eq!(client.init_version(), 2);
+        assert_eq!(client.name(), String::from_str(&env, "FixedToken"));
+        assert_eq!(client.decimals(), 7);
+    }
+    
+    #[test]
+    fn test_reinitialize_after_lock_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        client.lock_initialization();
+        let result = client.try_reinitialize(&2, &String::from_str(&env, "FixedToken"), &String::from_str(&env, "FT"), &7);
+        assert_eq!(result, Err(Ok(TokenError::InitializationLocked)));
+    }
+    
+    fn funded_holder(env: &Env, client: &MultiExtensionTokenClient, amount: i128) -> Address {
+        let minter = Address::generate(env);
+        client.grant_role(&minter, &Symbol::new(env, "minter"), &u64::MAX);
+        let holder = Address::generate(env);
+        client.allow_user(&holder);
+        client.mint(&minter, &holder, &amount);
+        holder
+    }
+    
+    #[test]
+    fn test_transfer_from_spends_allowance() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.allow_user(&recipient);
+        
+        client.approve(&holder, &spender, &300, &1000);
+        client.transfer_from(&spender, &holder, &recipient, &120);
+        
+        assert_eq!(client.allowance(&holder, &spender), 180);
+        assert_eq!(client.balance(&holder), 380);
+        assert_eq!(client.balance(&recipient), 120);
+    }
+    
+    #[test]
+    fn test_transfer_from_over_allowance_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.allow_user(&recipient);
+        
+        client.approve(&holder, &spender, &100, &1000);
+        let result = client.try_transfer_from(&spender, &holder, &recipient, &101);
+        assert_eq!(result, Err(Ok(TokenError::InsufficientAllowance)));
+    }
+    
+    #[test]
+    fn test_negative_transfer_from_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let spender = Address::generate(&env);
+        let victim = funded_holder(&env, &client, 500);
+        
+        // A negative amount would pull the victim's funds back to the holder and grow the allowance
+        let result = client.try_transfer_from(&spender, &holder, &victim, &-500);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+        assert!(!client.can_transfer(&holder, &victim, &-500));
+        
+        assert_eq!(client.balance(&victim), 500);
+        assert_eq!(client.allowance(&holder, &spender), 0);
+    }
+    
+    #[test]
+    fn test_burn_rejects_negative_amount_and_blocked_holder() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let spender = Address::generate(&env);
+        let supply = client.total_supply();
+        
+        // A negative burn would mint unbacked tokens
+        assert_eq!(client.try_burn(&holder, &-100), Err(Ok(TokenError::InvalidAmount)));
+        assert_eq!(client.try_burn_from(&spender, &holder, &-100), Err(Ok(TokenError::InvalidAmount)));
+        
+        client.block_user(&holder);
+        assert_eq!(client.try_burn(&holder, &100), Err(Ok(TokenError::Blocked)));
+        
+        assert_eq!(client.balance(&holder), 500);
+        assert_eq!(client.total_supply(), supply);
+    }
+    
+    #[test]
+    fn test_batch_transfer() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let (first, second) = (Address::generate(&env), Address::generate(&env));
+        client.allow_user(&first);
+        client.allow_user(&second);
+        
+        client.batch_transfer(
+            &holder,
+            &Vec::from_array(&env, [first.clone(), second.clone()]),
+            &Vec::from_array(&env, [100i128, 250i128]),
+        );
+        
+        assert_eq!(client.balance(&holder), 150);
+        assert_eq!(client.balance(&first), 100);
+        assert_eq!(client.balance(&second), 250);
+    }
+    
+    #[test]
+    fn test_batch_transfer_length_mismatch() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let recipient = Address::generate(&env);
+        client.allow_user(&recipient);
+        
+        let result = client.try_batch_transfer(&holder, &Vec::from_array(&env, [recipient]), &Vec::from_array(&env, [10i128, 20i128]));
+        assert_eq!(result, Err(Ok(TokenError::LengthMismatch)));
+    }
+    
+    #[test]
+    fn test_batch_transfer_insufficient_balance() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let (first, second) = (Address::generate(&env), Address::generate(&env));
+        client.allow_user(&first);
+        client.allow_user(&second);
+        
+        // Each amount fits the balance but the total does not
+        let result = client.try_batch_transfer(
+            &holder,
+            &Vec::from_array(&env, [first, second]),
+            &Vec::from_array(&env, [300i128, 300i128]),
+        );
+        assert_eq!(result, Err(Ok(TokenError::InsufficientBalance)));
+    }
+    
+    #[test]
+    fn test_batch_transfer_reverts_on_blocked_recipient() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        let (first, blocked) = (Address::generate(&env), Address::generate(&env));
+        client.allow_user(&first);
+        client.allow_user(&blocked);
+        client.block_user(&blocked);
+        
+        let result = client.try_batch_transfer(
+            &holder,
+            &Vec::from_array(&env, [first.clone(), blocked]),
+            &Vec::from_array(&env, [100i128, 100i128]),
+        );
+        
+        assert_eq!(result, Err(Ok(TokenError::Blocked)));
+        assert_eq!(client.balance(&holder), 500);
+        assert_eq!(client.balance(&first), 0);
+    }
+    
+    #[test]
+    fn test_balances_outlive_default_ttl() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        
+        // Balances are per-account persistent entries rather than part of the instance
+        env.as_contract(&client.address, || {
+            assert!(env.storage().persistent().has(&DataKey::Balance(holder.clone())));
+            assert!(!env.storage().instance().has(&DataKey::Balance(holder.clone())));
+        });
+        
+        // Well past the default entry TTL, but within the TTL extended on write
+        env.ledger().with_mut(|li| li.sequence_number += 20 * DAY_IN_LEDGERS);
+        assert_eq!(client.balance(&holder), 500);
+        assert!(client.allowed(&holder));
+    }
+    
+    fn assert_can_transfer_matches(client: &MultiExtensionTokenClient, from: &Address, to: &Address, amount: i128) {
+        let predicted = client.can_transfer(from, to, &amount);
+        assert_eq!(client.try_transfer(from, to, &amount).is_ok(), predicted);
+    }
+    
+    #[test]
+    fn test_can_transfer_matches_transfer_outcome() {
+        let env = Env::default();
+        let client = setup(&env);
+        let operator = Address::generate(&env);
+        client.grant_role(&operator, &Symbol::new(&env, "pauser"), &u64::MAX);
+        client.grant_role(&operator, &Symbol::new(&env, "minter"), &u64::MAX);
+        
+        let holder = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.allow_user(&holder);
+        client.mint(&operator, &holder, &100);
+        
+        // Recipient not on the allowlist
+        assert!(!client.can_transfer(&holder, &recipient, &10));
+        assert_can_transfer_matches(&client, &holder, &recipient, 10);
+        
+        client.allow_user(&recipient);
+        assert!(client.can_transfer(&holder, &recipient, &10));
+        assert_eq!(client.transfer_preview(&holder, &recipient, &10), (90, 10));
+        assert_can_transfer_matches(&client, &holder, &recipient, 10);
+        
+        // Blocked sender
+        client.block_user(&holder);
+        assert_can_transfer_matches(&client, &holder, &recipient, 10);
+        client.unblock_user(&holder);
+        
+        // Paused contract
+        client.pause(&operator);
+        assert!(!client.can_transfer(&holder, &recipient, &10));
+        assert_eq!(client.transfer_preview(&holder, &recipient, &10), (90, 10));
+        assert_can_transfer_matches(&client, &holder, &recipient, 10);
+        client.unpause(&operator);
+        
+        // Overdraft
+        assert_can_transfer_matches(&client, &holder, &recipient, 1000);
+        assert_eq!(client.balance(&holder), 90);
+        
+        // Self-transfer: the preview and the transfer both leave the balance as it was
+        assert_eq!(client.transfer_preview(&holder, &holder, &10), (90, 90));
+        client.transfer(&holder, &holder, &10);
+        assert_eq!(client.balance(&holder), 90);
+    }
+    
+    #[test]
+    fn test_role_lapses_after_expiry() {
+        let env = Env::default();
+        let client = setup(&env);
+        let minter_role = Symbol::new(&env, "minter");
+        let minter = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        
+        client.grant_role(&minter, &minter_role, &100);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        assert!(client.has_role(&minter, &minter_role));
+        client.mint(&minter, &recipient, &10);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        assert!(!client.has_role(&minter, &minter_role));
+        assert_eq!(client.try_mint(&minter, &recipient, &10), Err(Ok(TokenError::Unauthorized)));
+        assert_eq!(client.balance(&recipient), 10);
+    }
+    
+    #[test]
+    fn test_pause_requires_pauser_role() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        // The contract itself holds no roles, and neither does an arbitrary caller
+        let result = client.try_pause(&Address::generate(&env));
+        assert_eq!(result, Err(Ok(TokenError::Unauthorized)));
+    }
+    
+    #[test]
+    fn test_mint_requires_minter_role() {
+        let env = Env::default();
+        let client = setup(&env);
+        let outsider = Address::generate(&env);
+        
+        let result = client.try_mint(&outsider, &outsider, &1000);
+        assert_eq!(result, Err(Ok(TokenError::Unauthorized)));
+    }
+    
+    #[test]
+    fn test_approve_rejects_past_expiration() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 500);
+        env.ledger().with_mut(|li| li.sequence_number = 100);
+        
+        let result = client.try_approve(&holder, &Address::generate(&env), &100, &99);
+        assert_eq!(result, Err(Ok(TokenError::InvalidExpiration)));
+    }
+    
+    #[test]
+    fn test_sep41_interface_via_token_client() {
+        let env = Env::default();
+        let client = setup(&env);
+        let token = token::Client::new(&env, &client.address);
+        let holder = funded_holder(&env, &client, 500);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.allow_user(&recipient);
+        
+        assert_eq!(token.name(), String::from_str(&env, "MultiToken"));
+        assert_eq!(token.symbol(), String::from_str(&env, "MT"));
+        assert_eq!(token.decimals(), 18);
+        
+        token.approve(&holder, &spender, &200, &1000);
+        assert_eq!(token.allowance(&holder, &spender), 200);
+        
+        token.transfer(&holder, &recipient, &100);
+        token.transfer_from(&spender, &holder, &recipient, &100);
+        token.burn(&recipient, &50);
+        token.burn_from(&spender, &holder, &100);
+        
+        assert_eq!(token.allowance(&holder, &spender), 0);
+        assert_eq!(token.balance(&holder), 200);
+        assert_eq!(token.balance(&recipient), 150);
+        assert_eq!(client.total_supply(), 1000350);
+    }
+    
+    #[test]
+    fn test_mint_past_i128_max_reverts() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 1);
+        let minter = Address::generate(&env);
+        client.grant_role(&minter, &Symbol::new(&env, "minter"), &u64::MAX);
+        
+        // Total supply already holds the initial 1000000, so it overflows before the balance does
+        let result = client.try_mint(&minter, &holder, &(i128::MAX - 10));
+        assert_eq!(result, Err(Ok(TokenError::Overflow)));
+        assert_eq!(client.balance(&holder), 1);
+        assert_eq!(client.total_supply(), 1000001);
+    }
+    
+    #[test]
+    fn test_mint_rejects_non_positive_amount() {
+        let env = Env::default();
+        let client = setup(&env);
+        let holder = funded_holder(&env, &client, 1);
+        let minter = Address::generate(&env);
+        client.grant_role(&minter, &Symbol::new(&env, "minter"), &u64::MAX);
+        
+        assert_eq!(client.try_mint(&minter, &holder, &0), Err(Ok(TokenError::InvalidAmount)));
+        assert_eq!(client.try_mint(&minter, &holder, &-100), Err(Ok(TokenError::InvalidAmount)));
+        assert_eq!(client.balance(&holder), 1);
+        assert_eq!(client.total_supply(), 1000001);
+    }
 }
@@ -6,7 +6,13 @@ use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol};
 #[derive(Clone)]
 pub enum DataKey {
     Owner,
+    PendingOwner,
     Counter,
+    Cooldown,
+    LastIncrement,
+    Step,
+    MinBound,
+    MaxBound,
 }
 
 #[contracttype]
@@ -24,7 +30,8 @@ pub struct OwnableCounter;
 impl OwnableCounter {
     /// Initialize the counter with an owner
     pub fn initialize(env: Env, owner: Address) {
-        if env.storage().instance().has(&DataKey::Owner) {
+        // The counter outlives a renounced owner, so it marks initialization
+        if env.storage().instance().has(&DataKey::Counter) {
             panic!("Already initialized");
         }
         
@@ -34,42 +41,104 @@ impl OwnableCounter {
         env.events().publish((Symbol::new(&env, "initialized"),), owner);
     }
     
-    /// Get the current owner
-    pub fn owner(env: Env) -> Address {
-        env.storage().instance().get(&DataKey::Owner).unwrap()
+    /// Get the current owner (`None` once ownership is renounced)
+    pub fn owner(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Owner)
     }
     
-    /// Transfer ownership (only current owner)
+    /// Get the address nominated to take over ownership, if any
+    pub fn pending_owner(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingOwner)
+    }
+    
+    /// Nominate a new owner (only current owner); ownership moves once they accept
     pub fn transfer_ownership(env: Env, new_owner: Address) {
-        let current_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        current_owner.require_auth();
+        let current_owner = Self::require_owner(&env);
+        
+        env.storage().instance().set(&DataKey::PendingOwner, &new_owner);
+        env.events().publish((Symbol::new(&env, "ownership_transfer_started"),), (current_owner, new_owner));
+    }
+    
+    /// Accept a pending ownership transfer (only the pending owner)
+    pub fn accept_ownership(env: Env) {
+        let new_owner = Self::pending_owner(env.clone()).unwrap_or_else(|| panic!("No pending owner"));
+        new_owner.require_auth();
         
+        let previous_owner = Self::require_owner_exists(&env);
         env.storage().instance().set(&DataKey::Owner, &new_owner);
-        env.events().publish((Symbol::new(&env, "ownership_transferred"),), (current_owner, new_owner));
+        env.storage().instance().remove(&DataKey::PendingOwner);
+        env.events().publish((Symbol::new(&env, "ownership_transferred"),), (previous_owner, Some(new_owner)));
+    }
+    
+    /// Give up ownership for good (only current owner); owner-gated functions can no longer be called
+    pub fn renounce_ownership(env: Env) {
+        let current_owner = Self::require_owner(&env);
+        
+        env.storage().instance().remove(&DataKey::Owner);
+        env.storage().instance().remove(&DataKey::PendingOwner);
+        env.events().publish((Symbol::new(&env, "ownership_transferred"),), (current_owner, None::<Address>));
+    }
+    
+    /// Set the minimum number of seconds between increments (only owner, 0 disables)
+    pub fn set_cooldown(env: Env, cooldown_seconds: u64) {
+        Self::require_owner(&env);
+        
+        env.storage().instance().set(&DataKey::Cooldown, &cooldown_seconds);
+        env.events().publish((Symbol::new(&env, "cooldown_set"),), cooldown_seconds);
     }
     
-    /// Increment counter (only owner)
+    /// Get the configured increment cooldown in seconds
+    pub fn cooldown(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::Cooldown).unwrap_or(0)
+    }
+    
+    /// Set the amount `increment` and `decrement` move the counter by (only owner)
+    pub fn set_step(env: Env, step: i128) {
+        Self::require_owner(&env);
+        
+        if step <= 0 {
+            panic!("Step must be positive");
+        }
+        
+        env.storage().instance().set(&DataKey::Step, &step);
+        env.events().publish((Symbol::new(&env, "step_set"),), step);
+    }
+    
+    /// Get the default step size (1 unless set)
+    pub fn step(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::Step).unwrap_or(1)
+    }
+    
+    /// Increment counter by the step size (only owner)
     pub fn increment(env: Env) {
-        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        owner.require_auth();
+        Self::require_owner(&env);
         
-        let current_value: i128 = env.storage().instance().get(&DataKey::Counter).unwrap_or(0);
-        let new_value = current_value + 1;
+        Self::check_cooldown(&env);
+        Self::add_to_counter(&env, Self::step(env.clone()));
+    }
+    
+    /// Increment counter by `amount` (only owner)
+    pub fn increment_by(env: Env, amount: i128) {
+        Self::require_owner(&env);
+        Self::require_positive(amount);
         
-        env.storage().instance().set(&DataKey::Counter, &new_value);
-        env.events().publish((Symbol::new(&env, "incremented"),), new_value);
+        Self::check_cooldown(&env);
+        Self::add_to_counter(&env, amount);
     }
     
-    /// Decrement counter (only owner)
+    /// Decrement counter by the step size (only owner)
     pub fn decrement(env: Env) {
-        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        owner.require_auth();
+        Self::require_owner(&env);
         
-        let current_value: i128 = env.storage().instance().get(&DataKey::Counter).unwrap_or(0);
-        let new_value = current_value - 1;
+        Self::add_to_counter(&env, -Self::step(env.clone()));
+    }
+    
+    /// Decrement counter by `amount` (only owner)
+    pub fn decrement_by(env: Env, amount: i128) {
+        Self::require_owner(&env);
+        Self::require_positive(amount);
         
-        env.storage().instance().set(&DataKey::Counter, &new_value);
-        env.events().publish((Symbol::new(&env, "decremented"),), new_value);
+        Self::add_to_counter(&env, -amount);
     }
     
     /// Get current counter value (public)
@@ -79,31 +148,109 @@ impl OwnableCounter {
     
     /// Reset counter to zero (only owner)
     pub fn reset(env: Env) {
-        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        owner.require_auth();
+        Self::require_owner(&env);
         
-        env.storage().instance().set(&DataKey::Counter, &0i128);
+        Self::store_counter(&env, 0);
         env.events().publish((Symbol::new(&env, "reset"),), ());
     }
     
     /// Set counter to specific value (only owner)
     pub fn set_counter(env: Env, value: i128) {
-        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        Self::require_owner(&env);
+        
+        Self::store_counter(&env, value);
+        env.events().publish((Symbol::new(&env, "set_counter"),), value);
+    }
+    
+    /// Restrict the counter to `min..=max` (only owner); the current value must already be in range
+    pub fn set_bounds(env: Env, min: i128, max: i128) {
+        Self::require_owner(&env);
+        
+        if min > max {
+            panic!("Invalid bounds");
+        }
+        let value = Self::get_counter(env.clone());
+        if value < min || value > max {
+            panic!("Counter out of bounds");
+        }
+        
+        env.storage().instance().set(&DataKey::MinBound, &min);
+        env.storage().instance().set(&DataKey::MaxBound, &max);
+        env.events().publish((Symbol::new(&env, "bounds_set"),), (min, max));
+    }
+    
+    /// Get the inclusive range the counter is kept within (the full i128 range unless set)
+    pub fn bounds(env: Env) -> (i128, i128) {
+        let min = env.storage().instance().get(&DataKey::MinBound).unwrap_or(i128::MIN);
+        let max = env.storage().instance().get(&DataKey::MaxBound).unwrap_or(i128::MAX);
+        (min, max)
+    }
+    
+    /// Require the current owner's auth, rejecting the call once ownership is renounced
+    fn require_owner(env: &Env) -> Address {
+        let owner = Self::require_owner_exists(env);
         owner.require_auth();
+        owner
+    }
+    
+    fn require_owner_exists(env: &Env) -> Address {
+        env.storage().instance()
+            .get(&DataKey::Owner)
+            .unwrap_or_else(|| panic!("Contract has no owner"))
+    }
+    
+    fn require_positive(amount: i128) {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+    }
+    
+    /// Move the counter by `delta` and emit the matching event
+    fn add_to_counter(env: &Env, delta: i128) {
+        let current_value: i128 = env.storage().instance().get(&DataKey::Counter).unwrap_or(0);
+        let new_value = current_value.checked_add(delta).unwrap_or_else(|| panic!("Counter overflow"));
+        
+        Self::store_counter(env, new_value);
+        let event = if delta > 0 { "incremented" } else { "decremented" };
+        env.events().publish((Symbol::new(env, event),), new_value);
+    }
+    
+    /// Store a new counter value, rejecting it if outside the configured bounds
+    fn store_counter(env: &Env, value: i128) {
+        let (min, max) = Self::bounds(env.clone());
+        if value < min || value > max {
+            panic!("Counter out of bounds");
+        }
         
         env.storage().instance().set(&DataKey::Counter, &value);
-        env.events().publish((Symbol::new(&env, "set_counter"),), value);
+    }
+    
+    /// Reject the call if the last increment is within the cooldown, then record this one
+    fn check_cooldown(env: &Env) {
+        let now = env.ledger().timestamp();
+        let cooldown: u64 = env.storage().instance().get(&DataKey::Cooldown).unwrap_or(0);
+        
+        if cooldown > 0 {
+            if let Some(last) = env.storage().instance().get::<_, u64>(&DataKey::LastIncrement) {
+                if now < last + cooldown {
+                    panic!("Increment cooldown active");
+                }
+            }
+        }
+        
+        env.storage().instance().set(&DataKey::LastIncrement, &now);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use soroban_sdk::{testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke}, Address, Env, IntoVal};
 
     #[test]
     fn test_ownable_functionality() {
         let env = Env::default();
+        env.mock_all_auths();
         let contract_id = env.register_contract(None, OwnableCounter);
         let client = OwnableCounterClient::new(&env, &contract_id);
         
@@ -112,7 +259,7 @@ mod tests {
         
         // Initialize contract
         client.initialize(&owner);
-        assert_eq!(client.owner(), owner);
+        assert_eq!(client.owner(), Some(owner.clone()));
         assert_eq!(client.get_counter(), 0);
         
         // Test owner operations
@@ -128,8 +275,221 @@ mod tests {
         client.reset();
         assert_eq!(client.get_counter(), 0);
         
-        // Test ownership transfer
+        // Test two-step ownership transfer
         client.transfer_ownership(&new_owner);
-        assert_eq!(client.owner(), new_owner);
+        assert_eq!(client.owner(), Some(owner));
+        assert_eq!(client.pending_owner(), Some(new_owner.clone()));
+        
+        client.accept_ownership();
+        assert_eq!(client.owner(), Some(new_owner));
+        assert_eq!(client.pending_owner(), None);
+    }
+    
+    #[test]
+    fn test_non_pending_address_cannot_accept() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, OwnableCounter);
+        let client = OwnableCounterClient::new(&env, &contract_id);
+        
+        let owner = Address::generate(&env);
+        client.initialize(&owner);
+        client.mock_all_auths().transfer_ownership(&Address::generate(&env));
+        
+        // Only the outsider signs, so the pending owner's auth check fails
+        let outsider = Address::generate(&env);
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &outsider,
+                invoke: &MockAuthInvoke {
+                    contract: &contract_id,
+                    fn_name: "accept_ownership",
+                    args: ().into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_accept_ownership();
+        
+        assert!(result.is_err());
+        assert_eq!(client.owner(), Some(owner));
+    }
+    
+    #[test]
+    #[should_panic(expected = "No pending owner")]
+    fn test_accept_without_pending_owner_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, OwnableCounter);
+        let client = OwnableCounterClient::new(&env, &contract_id);
+        
+        client.initialize(&Address::generate(&env));
+        client.accept_ownership();
+    }
+    
+    #[test]
+    #[should_panic(expected = "Increment cooldown active")]
+    fn test_increment_within_cooldown_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, OwnableCounter);
+        let client = OwnableCounterClient::new(&env, &contract_id);
+        
+        client.initialize(&Address::generate(&env));
+        client.set_cooldown(&60);
+        
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+        client.increment();
+        
+        env.ledger().with_mut(|li| li.timestamp = 1059);
+        client.increment();
+    }
+    
+    #[test]
+    fn test_increment_after_cooldown() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, OwnableCounter);
+        let client = OwnableCounterClient::new(&env, &contract_id);
+        
+        client.initialize(&Address::generate(&env));
+        client.set_cooldown(&60);
+        assert_eq!(client.cooldown(), 60);
+        
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+        client.increment();
+        
+        env.ledger().with_mut(|li| li.timestamp = 1060);
+        client.increment();
+        assert_eq!(client.get_counter(), 2);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Contract has no owner")]
+    fn test_renounced_contract_rejects_owner_calls() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, OwnableCounter);
+        let client = OwnableCounterClient::new(&env, &contract_id);
+        
+        client.initialize(&Address::generate(&env));
+        client.increment();
+        client.renounce_ownership();
+        assert_eq!(client.owner(), None);
+        assert_eq!(client.get_counter(), 1);
+        
+        client.increment();
+    }
+    
+    #[test]
+    #[should_panic(expected = "Already initialized")]
+    fn test_renounced_contract_cannot_be_reinitialized() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, OwnableCounter);
+        let client = OwnableCounterClient::new(&env, &contract_id);
+        
+        client.initialize(&Address::generate(&env));
+        client.renounce_ownership();
+        client.initialize(&Address::generate(&env));
+    }
+    
+    fn setup(env: &Env) -> OwnableCounterClient<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, OwnableCounter);
+        let client = OwnableCounterClient::new(env, &contract_id);
+        client.initialize(&Address::generate(env));
+        client
+    }
+    
+    #[test]
+    fn test_increment_by_and_step() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        client.increment_by(&5);
+        assert_eq!(client.get_counter(), 5);
+        client.decrement_by(&2);
+        assert_eq!(client.get_counter(), 3);
+        
+        client.set_step(&10);
+        assert_eq!(client.step(), 10);
+        client.increment();
+        assert_eq!(client.get_counter(), 13);
+        client.decrement();
+        assert_eq!(client.get_counter(), 3);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Step must be positive")]
+    fn test_zero_step_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        client.set_step(&0);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Amount must be positive")]
+    fn test_negative_increment_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        client.increment_by(&-3);
+    }
+    
+    #[test]
+    fn test_bounds_allow_boundary_values() {
+        let env = Env::default();
+        let client = setup(&env);
+        client.set_bounds(&-5, &5);
+        assert_eq!(client.bounds(), (-5, 5));
+        
+        client.increment_by(&5);
+        assert_eq!(client.get_counter(), 5);
+        client.set_counter(&-5);
+        assert_eq!(client.get_counter(), -5);
+        client.reset();
+        assert_eq!(client.get_counter(), 0);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Counter out of bounds")]
+    fn test_increment_past_max_bound_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        client.set_bounds(&0, &5);
+        
+        client.increment_by(&5);
+        client.increment();
+    }
+    
+    #[test]
+    #[should_panic(expected = "Counter out of bounds")]
+    fn test_decrement_past_min_bound_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        client.set_bounds(&-1, &5);
+        
+        client.decrement();
+        client.decrement();
+    }
+    
+    #[test]
+    #[should_panic(expected = "Counter overflow")]
+    fn test_increment_at_i128_max_overflows() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        client.set_counter(&i128::MAX);
+        client.increment();
+    }
+    
+    #[test]
+    #[should_panic(expected = "Counter overflow")]
+    fn test_decrement_at_i128_min_overflows() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        client.set_counter(&i128::MIN);
+        client.decrement();
     }
 }
@@ -1,18 +1,23 @@
 // Simplified pausable token contract demonstrating pausable pattern
 // Uses basic Soroban SDK features without OpenZeppelin dependencies
-use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, String, Symbol};
+use crate::token_error::TokenError;
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Balance(Address),
-    Allowance(Address, Address),
+    Allowance(Address, Address), // (amount, live_until_ledger)
     Admin,
     Paused,
+    ResumeAt,            // timestamp at which a scheduled pause lifts itself
+    PauseReason,
     TokenName,
     TokenSymbol,
     TokenDecimals,
     TotalSupply,
+    DailyLimit(Address),
+    DailySpent(Address), // (window_start, amount transferred in window)
 }
 
 #[contracttype]
@@ -23,6 +28,9 @@ pub enum PausableEvent {
     PausableTransfer(Address, Address, i128),
 }
 
+/// Length of the daily transfer limit window in seconds
+const DAY_IN_SECONDS: u64 = 86400;
+
 #[contract]
 pub struct PausableToken;
 
@@ -32,13 +40,13 @@ impl PausableToken {
     pub fn initialize(
         env: Env,
         admin: Address,
-        name: Symbol,
-        symbol: Symbol,
+        name: String,
+        symbol: String,
         decimals: u32,
         total_supply: i128,
-    ) {
+    ) -> Result<(), TokenError> {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            return Err(TokenError::AlreadyInitialized);
         }
         
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -48,81 +56,173 @@ impl PausableToken {
         env.storage().instance().set(&DataKey::TotalSupply, &total_supply);
         env.storage().instance().set(&DataKey::Balance(admin.clone()), &total_supply);
         env.storage().instance().set(&DataKey::Paused, &false);
+        Ok(())
     }
     
-    /// Check if contract is paused
+    /// Check if contract is paused, treating a scheduled pause as lifted once its resume time passes
     pub fn paused(env: Env) -> bool {
-        env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+        let paused: bool = env.storage().instance().get(&DataKey::Paused).unwrap_or(false);
+        match Self::resume_at(env.clone()) {
+            Some(resume_at) if paused => env.ledger().timestamp() < resume_at,
+            _ => paused,
+        }
+    }
+    
+    /// Get the time a scheduled pause lifts itself, if one is set
+    pub fn resume_at(env: Env) -> Option<u64> {
+        env.storage().instance().get(&DataKey::ResumeAt)
+    }
+    
+    /// Pause the contract until `resume_at`, after which it resumes without an `unpause` (admin only)
+    pub fn pause_until(env: Env, resume_at: u64) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        if resume_at <= env.ledger().timestamp() {
+            return Err(TokenError::InvalidResumeTime);
+        }
+        if Self::paused(env.clone()) {
+            return Err(TokenError::AlreadyPaused);
+        }
+        
+        env.storage().instance().set(&DataKey::Paused, &true);
+        env.storage().instance().set(&DataKey::ResumeAt, &resume_at);
+        env.events().publish((Symbol::new(&env, "paused"),), resume_at);
+        Ok(())
+    }
+    
+    /// Get the reason given for the current pause, if any
+    pub fn pause_reason(env: Env) -> Option<Symbol> {
+        env.storage().instance().get(&DataKey::PauseReason)
     }
     
-    /// Pause the contract (admin only)
-    pub fn pause(env: Env) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    /// Pause the contract, recording why (admin only)
+    pub fn pause(env: Env, reason: Symbol) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
         if Self::paused(env.clone()) {
-            panic!("Contract already paused");
+            return Err(TokenError::AlreadyPaused);
         }
         
         env.storage().instance().set(&DataKey::Paused, &true);
-        env.events().publish((Symbol::new(&env, "paused"),), ());
+        env.storage().instance().remove(&DataKey::ResumeAt);
+        env.storage().instance().set(&DataKey::PauseReason, &reason);
+        env.events().publish((Symbol::new(&env, "paused"),), reason);
+        Ok(())
     }
     
     /// Unpause the contract (admin only)
-    pub fn unpause(env: Env) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn unpause(env: Env) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
         if !Self::paused(env.clone()) {
-            panic!("Contract not paused");
+            return Err(TokenError::NotPaused);
         }
         
         env.storage().instance().set(&DataKey::Paused, &false);
+        env.storage().instance().remove(&DataKey::ResumeAt);
+        env.storage().instance().remove(&DataKey::PauseReason);
         env.events().publish((Symbol::new(&env, "unpaused"),), ());
+        Ok(())
     }
     
     /// Transfer tokens (only when not paused)
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
         from.require_auth();
         
-        // Check if contract is paused
-        if Self::paused(env.clone()) {
-            panic!("Contract is paused");
+        Self::transfer_checked(&env, from, to, amount)
+    }
+    
+    /// Approve a spender to transfer tokens on behalf of `from` until `live_until_ledger` (allowed while paused)
+    pub fn approve(
+        env: Env,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        live_until_ledger: u32,
+    ) -> Result<(), TokenError> {
+        from.require_auth();
+        
+        if amount < 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if amount > 0 && live_until_ledger < env.ledger().sequence() {
+            return Err(TokenError::InvalidExpiration);
         }
         
-        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
-        if from_balance < amount {
-            panic!("Insufficient balance");
+        env.storage().instance().set(&DataKey::Allowance(from.clone(), spender.clone()), &(amount, live_until_ledger));
+        env.events().publish((Symbol::new(&env, "approve"),), (from, spender, amount, live_until_ledger));
+        Ok(())
+    }
+    
+    /// Get the amount a spender may transfer on behalf of `from` (0 once the approval has expired)
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        let (amount, live_until_ledger): (i128, u32) = env.storage().instance()
+            .get(&DataKey::Allowance(from, spender))
+            .unwrap_or((0, 0));
+        
+        if live_until_ledger < env.ledger().sequence() {
+            0
+        } else {
+            amount
         }
+    }
+    
+    /// Transfer tokens on behalf of `from` using the spender's allowance (only when not paused)
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        spender.require_auth();
         
-        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        Self::transfer_checked(&env, from, to, amount)
+    }
+    
+    /// Burn tokens from `from`'s own balance (only when not paused)
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        from.require_auth();
         
-        env.storage().instance().set(&DataKey::Balance(from.clone()), &(from_balance - amount));
-        env.storage().instance().set(&DataKey::Balance(to.clone()), &(to_balance + amount));
+        Self::burn_checked(&env, from, amount)
+    }
+    
+    /// Burn tokens on behalf of `from` using the spender's allowance (only when not paused)
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) -> Result<(), TokenError> {
+        spender.require_auth();
         
-        env.events().publish((Symbol::new(&env, "transfer"),), (from, to, amount));
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        Self::burn_checked(&env, from, amount)
     }
     
-    /// Burn tokens (only when not paused, admin only)
-    pub fn burn(env: Env, amount: i128) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    /// Set the maximum amount an account may transfer per 24h window (admin only, 0 = unlimited)
+    pub fn set_daily_limit(env: Env, account: Address, limit: i128) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
-        if Self::paused(env.clone()) {
-            panic!("Contract is paused");
-        }
-        
-        let admin_balance: i128 = env.storage().instance().get(&DataKey::Balance(admin.clone())).unwrap_or(0);
-        if admin_balance < amount {
-            panic!("Insufficient balance to burn");
+        if limit < 0 {
+            return Err(TokenError::InvalidAmount);
         }
         
-        let total_supply: i128 = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
-        
-        env.storage().instance().set(&DataKey::Balance(admin.clone()), &(admin_balance - amount));
-        env.storage().instance().set(&DataKey::TotalSupply, &(total_supply - amount));
+        env.storage().instance().set(&DataKey::DailyLimit(account.clone()), &limit);
+        env.events().publish((Symbol::new(&env, "daily_limit_set"),), (account, limit));
+        Ok(())
+    }
+    
+    /// Get the daily transfer limit for an account (0 = unlimited)
+    pub fn daily_limit(env: Env, account: Address) -> i128 {
+        env.storage().instance().get(&DataKey::DailyLimit(account)).unwrap_or(0)
+    }
+    
+    /// Get the amount an account has transferred in the current window
+    pub fn daily_spent(env: Env, account: Address) -> i128 {
+        let (window_start, spent): (u64, i128) = env.storage().instance()
+            .get(&DataKey::DailySpent(account))
+            .unwrap_or((0, 0));
         
-        env.events().publish((Symbol::new(&env, "burn"),), (admin, amount));
+        if env.ledger().timestamp() >= window_start + DAY_IN_SECONDS {
+            0
+        } else {
+            spent
+        }
     }
     
     /// Get balance
@@ -131,12 +231,12 @@ impl PausableToken {
     }
     
     /// Get token name
-    pub fn name(env: Env) -> Symbol {
+    pub fn name(env: Env) -> String {
         env.storage().instance().get(&DataKey::TokenName).unwrap()
     }
     
     /// Get token symbol
-    pub fn symbol(env: Env) -> Symbol {
+    pub fn symbol(env: Env) -> String {
         env.storage().instance().get(&DataKey::TokenSymbol).unwrap()
     }
     
@@ -149,16 +249,112 @@ impl PausableToken {
     pub fn total_supply(env: Env) -> i128 {
         env.storage().instance().get(&DataKey::TotalSupply).unwrap()
     }
+    
+    fn admin(env: &Env) -> Result<Address, TokenError> {
+        env.storage().instance().get(&DataKey::Admin).ok_or(TokenError::NotInitialized)
+    }
+    
+    /// Apply the pause and daily limit checks, then move the balance
+    fn transfer_checked(env: &Env, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        if Self::paused(env.clone()) {
+            return Err(TokenError::Paused);
+        }
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
+        if from_balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        Self::record_daily_spend(env, &from, amount)?;
+        
+        env.storage().instance().set(&DataKey::Balance(from.clone()), &from_balance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        // Read the recipient after the debit so a transfer to yourself nets to zero
+        let to_balance: i128 = env.storage().instance().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+        env.storage().instance().set(&DataKey::Balance(to.clone()), &to_balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+        
+        env.events().publish((Symbol::new(env, "transfer"),), (from, to, amount));
+        Ok(())
+    }
+    
+    /// Apply the pause check, then remove the tokens from the balance and total supply
+    fn burn_checked(env: &Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        if Self::paused(env.clone()) {
+            return Err(TokenError::Paused);
+        }
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let from_balance: i128 = env.storage().instance().get(&DataKey::Balance(from.clone())).unwrap_or(0);
+        if from_balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        let total_supply: i128 = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
+        
+        env.storage().instance().set(&DataKey::Balance(from.clone()), &from_balance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        env.storage().instance().set(&DataKey::TotalSupply, &total_supply.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        
+        env.events().publish((Symbol::new(env, "burn"),), (from, amount));
+        Ok(())
+    }
+    
+    /// Deduct `amount` from the spender's allowance, keeping its expiration
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) -> Result<(), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        let (_, live_until_ledger): (i128, u32) = env.storage().instance().get(&key).unwrap_or((0, 0));
+        env.storage().instance().set(&key, &(allowance.checked_sub(amount).ok_or(TokenError::Overflow)?, live_until_ledger));
+        Ok(())
+    }
+    
+    /// Add `amount` to the account's rolling daily total, rejecting it if over the limit
+    fn record_daily_spend(env: &Env, account: &Address, amount: i128) -> Result<(), TokenError> {
+        let limit: i128 = env.storage().instance().get(&DataKey::DailyLimit(account.clone())).unwrap_or(0);
+        if limit == 0 {
+            return Ok(());
+        }
+        
+        let now = env.ledger().timestamp();
+        let (mut window_start, mut spent): (u64, i128) = env.storage().instance()
+            .get(&DataKey::DailySpent(account.clone()))
+            .unwrap_or((now, 0));
+        
+        if now >= window_start + DAY_IN_SECONDS {
+            window_start = now;
+            spent = 0;
+        }
+        
+        spent = spent.checked_add(amount).ok_or(TokenError::Overflow)?;
+        if spent > limit {
+            return Err(TokenError::DailyLimitExceeded);
+        }
+        
+        env.storage().instance().set(&DataKey::DailySpent(account.clone()), &(window_start, spent));
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env};
 
     #[test]
     fn test_pausable_functionality() {
         let env = Env::default();
+        env.mock_all_auths();
         let contract_id = env.register_contract(None, PausableToken);
         let client = PausableTokenClient::new(&env, &contract_id);
         
@@ -168,8 +364,8 @@ mod tests {
         // Initialize token
         client.initialize(
             &admin,
-            &Symbol::new(&env, "PauseToken"),
-            &Symbol::new(&env, "PST"),
+            &String::from_str(&env, "PauseToken"),
+            &String::from_str(&env, "PST"),
             &18,
             &1000000,
         );
@@ -182,11 +378,206 @@ mod tests {
         assert_eq!(client.balance(&user1), 1000);
         
         // Pause contract
-        client.pause();
+        client.pause(&Symbol::new(&env, "maintenance"));
         assert!(client.paused());
         
         // Unpause contract
         client.unpause();
         assert!(!client.paused());
     }
+    
+    fn setup_with_limit(env: &Env) -> (PausableTokenClient<'_>, Address, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, PausableToken);
+        let client = PausableTokenClient::new(env, &contract_id);
+        
+        let admin = Address::generate(env);
+        let user = Address::generate(env);
+        client.initialize(
+            &admin,
+            &String::from_str(env, "PauseToken"),
+            &String::from_str(env, "PST"),
+            &18,
+            &1000000,
+        );
+        client.transfer(&admin, &user, &1000);
+        client.set_daily_limit(&user, &300);
+        
+        (client, admin, user)
+    }
+    
+    #[test]
+    fn test_daily_limit_exceeded() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        
+        client.transfer(&user, &admin, &200);
+        client.transfer(&user, &admin, &100);
+        assert_eq!(client.daily_spent(&user), 300);
+        
+        let result = client.try_transfer(&user, &admin, &1);
+        assert_eq!(result, Err(Ok(TokenError::DailyLimitExceeded)));
+    }
+    
+    #[test]
+    fn test_daily_limit_resets_after_window() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        
+        client.transfer(&user, &admin, &300);
+        assert_eq!(client.daily_spent(&user), 300);
+        
+        env.ledger().with_mut(|li| li.timestamp += 86400);
+        assert_eq!(client.daily_spent(&user), 0);
+        
+        client.transfer(&user, &admin, &300);
+        assert_eq!(client.balance(&user), 400);
+    }
+    
+    #[test]
+    fn test_pause_until_resumes_automatically() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        
+        client.pause_until(&1000);
+        assert!(client.paused());
+        assert_eq!(client.try_transfer(&user, &admin, &100), Err(Ok(TokenError::Paused)));
+        
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+        assert!(!client.paused());
+        client.transfer(&user, &admin, &100);
+        assert_eq!(client.balance(&user), 900);
+        
+        // A manual pause after the scheduled one lapses stays in place
+        client.pause(&Symbol::new(&env, "maintenance"));
+        assert_eq!(client.resume_at(), None);
+        env.ledger().with_mut(|li| li.timestamp = 5000);
+        assert!(client.paused());
+    }
+    
+    #[test]
+    fn test_unpause_lifts_scheduled_pause_early() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        
+        client.pause_until(&1000);
+        client.unpause();
+        
+        assert!(!client.paused());
+        client.transfer(&user, &admin, &100);
+    }
+    
+    #[test]
+    fn test_transfer_from_spends_allowance() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        let spender = Address::generate(&env);
+        
+        client.approve(&user, &spender, &250, &1000);
+        client.transfer_from(&spender, &user, &admin, &200);
+        
+        assert_eq!(client.allowance(&user, &spender), 50);
+        assert_eq!(client.balance(&user), 800);
+        assert_eq!(client.daily_spent(&user), 200);
+    }
+    
+    #[test]
+    fn test_negative_amounts_rejected() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        let spender = Address::generate(&env);
+        
+        // A negative transfer_from would pull funds from `to` and grow the allowance
+        let result = client.try_transfer_from(&spender, &user, &admin, &-100);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+        let result = client.try_transfer(&user, &admin, &-100);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+        
+        // A negative burn would mint
+        let supply = client.total_supply();
+        let result = client.try_burn(&user, &-100);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+        let result = client.try_burn_from(&spender, &user, &-100);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+        
+        assert_eq!(client.balance(&user), 1000);
+        assert_eq!(client.total_supply(), supply);
+        assert_eq!(client.allowance(&user, &spender), 0);
+    }
+    
+    #[test]
+    fn test_self_transfer_keeps_balance() {
+        let env = Env::default();
+        let (client, _admin, user) = setup_with_limit(&env);
+        
+        client.transfer(&user, &user, &100);
+        assert_eq!(client.balance(&user), 1000);
+    }
+    
+    #[test]
+    fn test_transfer_from_rejected_while_paused() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        let spender = Address::generate(&env);
+        
+        // Approvals can still be set while paused
+        client.pause(&Symbol::new(&env, "maintenance"));
+        client.approve(&user, &spender, &250, &1000);
+        assert_eq!(client.allowance(&user, &spender), 250);
+        
+        let result = client.try_transfer_from(&spender, &user, &admin, &200);
+        assert_eq!(result, Err(Ok(TokenError::Paused)));
+    }
+    
+    #[test]
+    fn test_pause_reason_cleared_on_unpause() {
+        let env = Env::default();
+        let (client, _admin, _user) = setup_with_limit(&env);
+        assert_eq!(client.pause_reason(), None);
+        
+        client.pause(&Symbol::new(&env, "exploit"));
+        assert_eq!(client.pause_reason(), Some(Symbol::new(&env, "exploit")));
+        
+        client.unpause();
+        assert_eq!(client.pause_reason(), None);
+    }
+    
+    #[test]
+    fn test_expired_approval_cannot_be_spent() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        let spender = Address::generate(&env);
+        
+        client.approve(&user, &spender, &250, &100);
+        env.ledger().with_mut(|li| li.sequence_number = 101);
+        assert_eq!(client.allowance(&user, &spender), 0);
+        
+        let result = client.try_transfer_from(&spender, &user, &admin, &100);
+        assert_eq!(result, Err(Ok(TokenError::InsufficientAllowance)));
+    }
+    
+    #[test]
+    fn test_sep41_interface_via_token_client() {
+        let env = Env::default();
+        let (client, admin, user) = setup_with_limit(&env);
+        let token = token::Client::new(&env, &client.address);
+        let spender = Address::generate(&env);
+        
+        assert_eq!(token.name(), String::from_str(&env, "PauseToken"));
+        assert_eq!(token.symbol(), String::from_str(&env, "PST"));
+        assert_eq!(token.decimals(), 18);
+        assert_eq!(token.balance(&user), 1000);
+        
+        token.approve(&user, &spender, &200, &1000);
+        assert_eq!(token.allowance(&user, &spender), 200);
+        
+        token.transfer(&user, &admin, &50);
+        token.transfer_from(&spender, &user, &admin, &100);
+        token.burn(&user, &50);
+        token.burn_from(&spender, &user, &100);
+        
+        assert_eq!(token.allowance(&user, &spender), 0);
+        assert_eq!(token.balance(&user), 700);
+        assert_eq!(client.total_supply(), 999850);
+    }
 }
@@ -1,4 +1,14 @@
-use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, Bytes, BytesN, Env, Address, Symbol, Vec};
+use crate::token_error::TokenError;
+
+/// Maximum number of recipients in a single airdrop
+const MAX_AIRDROP_BATCH: u32 = 100;
+
+/// Maximum number of transfers in a single batch transfer
+const MAX_TRANSFER_BATCH: u32 = 100;
+
+/// Ledgers an idempotency key is remembered for (~1 day at 5s ledgers)
+const IDEMPOTENCY_KEY_TTL: u32 = 17280;
 
 #[contract]
 pub struct TokenContract;
@@ -6,9 +16,12 @@ pub struct TokenContract;
 #[contracttype]
 pub enum DataKey {
     Balance(Address),
-    Nonce(Address),
-    State(Address),
     Admin,
+    Finalized,
+    TotalSupply,
+    Allowance(Address, Address),
+    ProcessedTransfer(Address, BytesN<32>),
+    Metadata,
 }
 
 #[contracttype]
@@ -18,36 +31,535 @@ pub struct TokenMetadata {
     pub symbol: Symbol,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TokenEvent {
+    Transfer(Address, Address, i128),
+    Mint(Address, i128),
+    MemoTransfer(Address, Address, i128, Bytes),
+}
+
 #[contractimpl]
 impl TokenContract {
-    pub fn initialize(env: Env, admin: Address, decimal: u32, name: Symbol, symbol: Symbol) {
+    pub fn initialize(env: Env, admin: Address, decimal: u32, name: Symbol, symbol: Symbol) -> Result<(), TokenError> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(TokenError::AlreadyInitialized);
+        }
+        
         let metadata = TokenMetadata {
             decimal,
             name,
             symbol,
         };
         env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Metadata, &metadata);
+        Ok(())
+    }
+
+    pub fn name(env: Env) -> Symbol {
+        Self::metadata(&env).name
+    }
+
+    pub fn symbol(env: Env) -> Symbol {
+        Self::metadata(&env).symbol
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        Self::metadata(&env).decimal
     }
 
     pub fn balance(env: Env, id: Address) -> i128 {
         env.storage().persistent().get(&DataKey::Balance(id)).unwrap_or(0)
     }
 
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
         from.require_auth();
         
-        let from_balance = Self::balance(env.clone(), from.clone());
-        let to_balance = Self::balance(env.clone(), to.clone());
+        Self::move_balance(&env, from, to, amount)
+    }
+
+    pub fn transfer_idempotent(env: Env, from: Address, to: Address, amount: i128, key: BytesN<32>) -> Result<bool, TokenError> {
+        from.require_auth();
         
-        env.storage().persistent().set(&DataKey::Balance(from), &(from_balance - amount));
-        env.storage().persistent().set(&DataKey::Balance(to), &(to_balance + amount));
+        // Keys live in temporary storage so the history expires on its own
+        let processed_key = DataKey::ProcessedTransfer(from.clone(), key);
+        if env.storage().temporary().has(&processed_key) {
+            return Ok(false);
+        }
+        
+        Self::move_balance(&env, from, to, amount)?;
+        
+        env.storage().temporary().set(&processed_key, &true);
+        env.storage().temporary().extend_ttl(&processed_key, IDEMPOTENCY_KEY_TTL, IDEMPOTENCY_KEY_TTL);
+        Ok(true)
     }
 
-    pub fn mint(env: Env, to: Address, amount: i128) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    pub fn batch_transfer(env: Env, from: Address, transfers: Vec<(Address, i128, Bytes)>) -> Result<(), TokenError> {
+        from.require_auth();
+        
+        if transfers.len() > MAX_TRANSFER_BATCH {
+            return Err(TokenError::BatchTooLarge);
+        }
+        
+        let mut total_amount = 0i128;
+        for (_, amount, _) in transfers.iter() {
+            if amount < 0 {
+                return Err(TokenError::InvalidAmount);
+            }
+            total_amount = total_amount.checked_add(amount).ok_or(TokenError::Overflow)?;
+        }
+        if Self::balance(env.clone(), from.clone()) < total_amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        for (to, amount, memo) in transfers.iter() {
+            let from_balance = Self::balance(env.clone(), from.clone());
+            env.storage().persistent().set(&DataKey::Balance(from.clone()), &from_balance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+            let to_balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&DataKey::Balance(to.clone()), &to_balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+            env.events().publish(
+                (Symbol::new(&env, "memo_transfer"),),
+                TokenEvent::MemoTransfer(from.clone(), to, amount, memo),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128) -> Result<(), TokenError> {
+        from.require_auth();
+        
+        if amount < 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        env.storage().persistent().set(&DataKey::Allowance(from, spender), &amount);
+        Ok(())
+    }
+
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Allowance(from, spender)).unwrap_or(0)
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        spender.require_auth();
+        
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(TokenError::InsufficientAllowance);
+        }
+        
+        // An i128::MAX allowance is treated as infinite and never decremented
+        if allowance != i128::MAX {
+            env.storage().persistent().set(&DataKey::Allowance(from.clone(), spender), &allowance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        }
+        
+        Self::move_balance(&env, from, to, amount)
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
         admin.require_auth();
         
+        if Self::is_finalized(env.clone()) {
+            return Err(TokenError::MintingFinalized);
+        }
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
         let balance = Self::balance(env.clone(), to.clone());
-        env.storage().persistent().set(&DataKey::Balance(to), &(balance + amount));
+        env.storage().persistent().set(&DataKey::Balance(to.clone()), &balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+        env.storage().instance().set(&DataKey::TotalSupply, &Self::total_supply(env.clone()).checked_add(amount).ok_or(TokenError::Overflow)?);
+        env.events().publish((Symbol::new(&env, "mint"),), TokenEvent::Mint(to, amount));
+        Ok(())
+    }
+
+    pub fn airdrop(env: Env, recipients: Vec<Address>, amounts: Vec<i128>) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        if Self::is_finalized(env.clone()) {
+            return Err(TokenError::MintingFinalized);
+        }
+        
+        if recipients.len() != amounts.len() {
+            return Err(TokenError::LengthMismatch);
+        }
+        
+        if recipients.len() > MAX_AIRDROP_BATCH {
+            return Err(TokenError::BatchTooLarge);
+        }
+        
+        let mut total_amount = 0i128;
+        for i in 0..recipients.len() {
+            let recipient = recipients.get(i).unwrap();
+            let amount = amounts.get(i).unwrap();
+            if amount < 0 {
+                return Err(TokenError::InvalidAmount);
+            }
+            
+            let balance = Self::balance(env.clone(), recipient.clone());
+            env.storage().persistent().set(&DataKey::Balance(recipient.clone()), &balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+            total_amount = total_amount.checked_add(amount).ok_or(TokenError::Overflow)?;
+            env.events().publish((Symbol::new(&env, "mint"),), TokenEvent::Mint(recipient, amount));
+        }
+        
+        env.storage().instance().set(&DataKey::TotalSupply, &Self::total_supply(env.clone()).checked_add(total_amount).ok_or(TokenError::Overflow)?);
+        Ok(())
+    }
+
+    pub fn total_supply(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0)
+    }
+
+    pub fn finalize(env: Env) -> Result<(), TokenError> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::Finalized, &true);
+        Ok(())
+    }
+
+    pub fn is_finalized(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Finalized).unwrap_or(false)
+    }
+
+    fn admin(env: &Env) -> Result<Address, TokenError> {
+        env.storage().instance().get(&DataKey::Admin).ok_or(TokenError::NotInitialized)
+    }
+
+    fn metadata(env: &Env) -> TokenMetadata {
+        env.storage().instance().get(&DataKey::Metadata).unwrap()
+    }
+
+    /// Check the amount and balance, then move tokens and emit the transfer event; callers
+    /// handle authorization
+    fn move_balance(env: &Env, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        if amount <= 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        
+        let from_balance = Self::balance(env.clone(), from.clone());
+        if from_balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        
+        env.storage().persistent().set(&DataKey::Balance(from.clone()), &from_balance.checked_sub(amount).ok_or(TokenError::Overflow)?);
+        // Read the recipient after the debit so a transfer to yourself nets to zero
+        let to_balance = Self::balance(env.clone(), to.clone());
+        env.storage().persistent().set(&DataKey::Balance(to.clone()), &to_balance.checked_add(amount).ok_or(TokenError::Overflow)?);
+        env.events().publish((Symbol::new(env, "transfer"),), TokenEvent::Transfer(from, to, amount));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{testutils::{Address as _, Events}, Address, BytesN, Env, FromVal, IntoVal, Vec};
+
+    fn setup(env: &Env) -> TokenContractClient<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, TokenContract);
+        let client = TokenContractClient::new(env, &contract_id);
+        
+        client.initialize(
+            &Address::generate(env),
+            &7,
+            &Symbol::new(env, "Token"),
+            &Symbol::new(env, "TKN"),
+        );
+        client
+    }
+
+    #[test]
+    fn test_mint_before_finalize() {
+        let env = Env::default();
+        let client = setup(&env);
+        let user = Address::generate(&env);
+        
+        assert!(!client.is_finalized());
+        client.mint(&user, &500);
+        assert_eq!(client.balance(&user), 500);
+    }
+
+    #[test]
+    fn test_mint_after_finalize_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        client.finalize();
+        assert!(client.is_finalized());
+        let result = client.try_mint(&Address::generate(&env), &500);
+        assert_eq!(result, Err(Ok(TokenError::MintingFinalized)));
+    }
+
+    #[test]
+    fn test_airdrop() {
+        let env = Env::default();
+        let client = setup(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        
+        client.mint(&user1, &50);
+        assert_eq!(client.total_supply(), 50);
+        
+        let recipients = Vec::from_array(&env, [user1.clone(), user2.clone(), user3.clone()]);
+        let amounts = Vec::from_array(&env, [100i128, 200i128, 300i128]);
+        client.airdrop(&recipients, &amounts);
+        
+        assert_eq!(client.balance(&user1), 150);
+        assert_eq!(client.balance(&user2), 200);
+        assert_eq!(client.balance(&user3), 300);
+        assert_eq!(client.total_supply(), 650);
+        
+        // One mint event per recipient
+        let events = env.events().all();
+        let mint_events = events.slice(events.len() - 3..);
+        for (i, (_, topics, data)) in mint_events.iter().enumerate() {
+            let i = i as u32;
+            assert_eq!(topics, (Symbol::new(&env, "mint"),).into_val(&env));
+            assert_eq!(
+                TokenEvent::from_val(&env, &data),
+                TokenEvent::Mint(recipients.get(i).unwrap(), amounts.get(i).unwrap()),
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_allowance_not_decremented() {
+        let env = Env::default();
+        let client = setup(&env);
+        let owner = Address::generate(&env);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        
+        client.mint(&owner, &1000);
+        client.approve(&owner, &spender, &i128::MAX);
+        
+        client.transfer_from(&spender, &owner, &recipient, &100);
+        client.transfer_from(&spender, &owner, &recipient, &200);
+        
+        assert_eq!(client.allowance(&owner, &spender), i128::MAX);
+        assert_eq!(client.balance(&recipient), 300);
+        assert_eq!(client.balance(&owner), 700);
+    }
+
+    #[test]
+    fn test_transfer_idempotent() {
+        let env = Env::default();
+        let client = setup(&env);
+        let from = Address::generate(&env);
+        let to = Address::generate(&env);
+        client.mint(&from, &1000);
+        
+        let key = BytesN::from_array(&env, &[1; 32]);
+        assert!(client.transfer_idempotent(&from, &to, &100, &key));
+        assert!(!client.transfer_idempotent(&from, &to, &100, &key));
+        assert_eq!(client.balance(&to), 100);
+        
+        let new_key = BytesN::from_array(&env, &[2; 32]);
+        assert!(client.transfer_idempotent(&from, &to, &100, &new_key));
+        assert_eq!(client.balance(&to), 200);
+        assert_eq!(client.balance(&from), 800);
+    }
+
+    #[test]
+    fn test_metadata_stored() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        assert_eq!(client.name(), Symbol::new(&env, "Token"));
+        assert_eq!(client.symbol(), Symbol::new(&env, "TKN"));
+        assert_eq!(client.decimals(), 7);
+    }
+
+    #[test]
+    fn test_double_initialize_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        
+        let result = client.try_initialize(
+            &Address::generate(&env),
+            &7,
+            &Symbol::new(&env, "Token"),
+            &Symbol::new(&env, "TKN"),
+        );
+        assert_eq!(result, Err(Ok(TokenError::AlreadyInitialized)));
+    }
+
+    fn funded_account(env: &Env, client: &TokenContractClient) -> Address {
+        let account = Address::generate(env);
+        client.mint(&account, &100);
+        account
+    }
+
+    #[test]
+    fn test_transfer_overdraft_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let from = funded_account(&env, &client);
+        
+        let result = client.try_transfer(&from, &Address::generate(&env), &101);
+        assert_eq!(result, Err(Ok(TokenError::InsufficientBalance)));
+    }
+
+    #[test]
+    fn test_transfer_zero_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let from = funded_account(&env, &client);
+        
+        let result = client.try_transfer(&from, &Address::generate(&env), &0);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+    }
+
+    #[test]
+    fn test_transfer_negative_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let from = funded_account(&env, &client);
+        
+        let result = client.try_transfer(&from, &Address::generate(&env), &-50);
+        assert_eq!(result, Err(Ok(TokenError::InvalidAmount)));
+    }
+
+    #[test]
+    fn test_transfer_from_decrements_allowance() {
+        let env = Env::default();
+        let client = setup(&env);
+        let owner = funded_account(&env, &client);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        
+        client.approve(&owner, &spender, &60);
+        assert_eq!(client.allowance(&owner, &spender), 60);
+        
+        client.transfer_from(&spender, &owner, &recipient, &40);
+        assert_eq!(client.allowance(&owner, &spender), 20);
+        assert_eq!(client.balance(&recipient), 40);
+        assert_eq!(client.balance(&owner), 60);
+    }
+
+    #[test]
+    fn test_self_transfer_keeps_balance() {
+        let env = Env::default();
+        let client = setup(&env);
+        let owner = funded_account(&env, &client);
+        let spender = Address::generate(&env);
+        
+        client.transfer(&owner, &owner, &40);
+        assert_eq!(client.balance(&owner), 100);
+        
+        client.approve(&owner, &spender, &60);
+        client.transfer_from(&spender, &owner, &owner, &40);
+        assert_eq!(client.balance(&owner), 100);
+        assert_eq!(client.allowance(&owner, &spender), 20);
+        assert_eq!(client.total_supply(), 100);
+    }
+
+    #[test]
+    fn test_transfer_from_over_allowance_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let owner = funded_account(&env, &client);
+        let spender = Address::generate(&env);
+        
+        client.approve(&owner, &spender, &30);
+        let result = client.try_transfer_from(&spender, &owner, &Address::generate(&env), &31);
+        assert_eq!(result, Err(Ok(TokenError::InsufficientAllowance)));
+    }
+
+    #[test]
+    fn test_transfer_and_mint_emit_events() {
+        let env = Env::default();
+        let client = setup(&env);
+        let user = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        
+        client.mint(&user, &100);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(topics, (Symbol::new(&env, "mint"),).into_val(&env));
+        assert_eq!(TokenEvent::from_val(&env, &data), TokenEvent::Mint(user.clone(), 100));
+        
+        client.transfer(&user, &recipient, &40);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(topics, (Symbol::new(&env, "transfer"),).into_val(&env));
+        assert_eq!(TokenEvent::from_val(&env, &data), TokenEvent::Transfer(user, recipient, 40));
+    }
+
+    #[test]
+    fn test_batch_transfer_with_memos() {
+        let env = Env::default();
+        let client = setup(&env);
+        let payer = funded_account(&env, &client);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        
+        let transfers = Vec::from_array(&env, [
+            (alice.clone(), 30i128, Bytes::from_array(&env, b"salary-alice")),
+            (bob.clone(), 20i128, Bytes::from_array(&env, b"salary-bob")),
+            (carol.clone(), 10i128, Bytes::from_array(&env, b"bonus-carol")),
+        ]);
+        client.batch_transfer(&payer, &transfers);
+        
+        assert_eq!(client.balance(&payer), 40);
+        assert_eq!(client.balance(&alice), 30);
+        assert_eq!(client.balance(&bob), 20);
+        assert_eq!(client.balance(&carol), 10);
+        
+        let events = env.events().all();
+        let memo_events = events.slice(events.len() - 3..);
+        for (i, (_, topics, data)) in memo_events.iter().enumerate() {
+            let (to, amount, memo) = transfers.get(i as u32).unwrap();
+            assert_eq!(topics, (Symbol::new(&env, "memo_transfer"),).into_val(&env));
+            assert_eq!(TokenEvent::from_val(&env, &data), TokenEvent::MemoTransfer(payer.clone(), to, amount, memo));
+        }
+    }
+
+    #[test]
+    fn test_batch_transfer_rejects_total_over_balance() {
+        let env = Env::default();
+        let client = setup(&env);
+        let payer = funded_account(&env, &client);
+        
+        let transfers = Vec::from_array(&env, [
+            (Address::generate(&env), 60i128, Bytes::new(&env)),
+            (Address::generate(&env), 60i128, Bytes::new(&env)),
+        ]);
+        let result = client.try_batch_transfer(&payer, &transfers);
+        assert_eq!(result, Err(Ok(TokenError::InsufficientBalance)));
+    }
+
+    #[test]
+    fn test_mint_past_i128_max_reverts() {
+        let env = Env::default();
+        let client = setup(&env);
+        let user = Address::generate(&env);
+        
+        client.mint(&user, &(i128::MAX - 10));
+        let result = client.try_mint(&user, &11);
+        assert_eq!(result, Err(Ok(TokenError::Overflow)));
+        assert_eq!(client.balance(&user), i128::MAX - 10);
+        assert_eq!(client.total_supply(), i128::MAX - 10);
+    }
+
+    #[test]
+    fn test_mint_non_positive_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let user = Address::generate(&env);
+        let supply = client.total_supply();
+        
+        assert_eq!(client.try_mint(&user, &0), Err(Ok(TokenError::InvalidAmount)));
+        assert_eq!(client.try_mint(&user, &-100), Err(Ok(TokenError::InvalidAmount)));
+        assert_eq!(client.balance(&user), 0);
+        assert_eq!(client.total_supply(), supply);
     }
 }
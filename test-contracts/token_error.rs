@@ -0,0 +1,30 @@
+use soroban_sdk::contracterror;
+
+/// Reasons a mutating call on one of the token contracts can be rejected.
+/// Shared by `TokenContract`, `PausableToken`, `BlocklistToken`, and `MultiExtensionToken`;
+/// new variants go at the end so existing codes never change.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TokenError {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InsufficientBalance = 3,
+    Paused = 4,
+    NotAllowed = 5,
+    Blocked = 6,
+    Unauthorized = 7,
+    InvalidAmount = 8,
+    InsufficientAllowance = 9,
+    AlreadyPaused = 10,
+    NotPaused = 11,
+    BatchTooLarge = 12,
+    LengthMismatch = 13,
+    Overflow = 14,
+    MintingFinalized = 15,
+    DailyLimitExceeded = 16,
+    InvalidResumeTime = 17,
+    InitializationLocked = 18,
+    InvalidInitVersion = 19,
+    InvalidExpiration = 20,
+}
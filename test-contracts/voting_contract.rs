@@ -1,14 +1,51 @@
-use soroban_sdk::{contract, contractimpl, contracttype, Env, Address, Symbol, Bytes};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, token, vec, Env, Address, Symbol, Bytes, IntoVal, Vec};
+
+use crate::access_control_nft::AccessControlNFTClient;
+
+/// Maximum number of proposals returned by a single `list_proposals` call
+const MAX_PAGE_SIZE: u32 = 50;
 
 #[contract]
 pub struct VotingContract;
 
+/// Reasons `execute_proposal` can refuse to execute a proposal
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum VotingError {
+    ProposalNotFound = 1,
+    VotingNotEnded = 2,
+    ExecutionDelayNotElapsed = 3,
+    AlreadyExecuted = 4,
+    ReentrantExecution = 7,
+    InsufficientTreasury = 8,
+    ProposalCancelled = 9,
+    ProposalRejected = 10,
+}
+
 #[contracttype]
 pub enum DataKey {
     Proposal(u64),
     Vote(u64, Address),
     ProposalCount,
     VotingPeriod,
+    Initialized,
+    Notifier,
+    Executing,
+    TieRule,
+    ExecutionDelay,
+    BondConfig,
+    Bond(u64),
+    Escrowed(Address),
+    Delegate(Address),
+    Delegators(Address),
+    NftGate,
+    GovernanceToken,
+    Quorum,
+    PassThreshold,
+    Deposit(Address),
+    DepositedNft(u64),
+    LockedUntil(Address),
 }
 
 #[contracttype]
@@ -17,23 +54,240 @@ pub struct Proposal {
     pub title: Symbol,
     pub description: Bytes,
     pub creator: Address,
-    pub votes_for: u64,
-    pub votes_against: u64,
+    pub votes_for: i128,
+    pub votes_against: i128,
+    pub votes_abstain: i128,
     pub end_time: u64,
     pub executed: bool,
+    pub cancelled: bool,
+    pub rejected: bool,
+    pub action: ProposalAction,
+}
+
+/// How `execute_proposal` finalized a proposal once its vote closed
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalOutcome {
+    Executed,
+    QuorumNotMet,
+    DidNotPass,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendAction {
+    pub token: Address,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// Effect applied by `execute_proposal` once a proposal passes
+#[contracttype]
+#[derive(Clone)]
+pub enum ProposalAction {
+    NoOp,
+    SetVotingPeriod(u64),
+    TransferFunds(SpendAction),
+}
+
+/// Bond an executor must post to execute a spend proposal. The bond can be
+/// slashed into the treasury by `arbiter` within `challenge_window` seconds.
+#[contracttype]
+#[derive(Clone)]
+pub struct BondConfig {
+    pub token: Address,
+    pub amount: i128,
+    pub challenge_window: u64,
+    pub arbiter: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PostedBond {
+    pub executor: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub executed_at: u64,
+}
+
+/// NFT-weighted governance: each NFT from `collection` deposited with the
+/// contract counts as one vote, and proposers must have deposited at least
+/// `min_nfts_to_propose` of them.
+#[contracttype]
+#[derive(Clone)]
+pub struct NftGate {
+    pub collection: Address,
+    pub min_nfts_to_propose: u32,
+}
+
+/// How `voting_power` weighs each voter
+#[contracttype]
+#[derive(Clone)]
+pub enum VoteWeighting {
+    OnePerAddress,
+    Token(Address),
+    Nft(NftGate),
+}
+
+/// Governance rules fixed by `initialize`
+#[contracttype]
+#[derive(Clone)]
+pub struct VotingConfig {
+    pub voting_period: u64,
+    pub tie_rule: TieRule,
+    pub execution_delay: u64,
+    pub quorum: i128,
+    pub pass_threshold_bps: u32,
+    pub weighting: VoteWeighting,
+}
+
+/// Outcome of a proposal whose `votes_for` equals its `votes_against`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TieRule {
+    TieFails,
+    TiePasses,
 }
 
 #[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Vote {
     For,
     Against,
+    Abstain,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VotingEvent {
+    ProposalCancelled(u64, Address),
+}
+
+/// A cast ballot with the voting weight snapshotted when it was counted
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteRecord {
+    pub vote: Vote,
+    pub weight: i128,
 }
 
 #[contractimpl]
 impl VotingContract {
-    pub fn create_proposal(env: Env, creator: Address, title: Symbol, description: Bytes, voting_period: u64) -> u64 {
+    pub fn initialize(env: Env, config: VotingConfig, notifier: Option<Address>, bond: Option<BondConfig>) {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            panic!("Already initialized");
+        }
+        if config.voting_period == 0 {
+            panic!("Voting period must be positive");
+        }
+        if config.quorum < 0 || config.pass_threshold_bps > 10000 {
+            panic!("Invalid quorum or pass threshold");
+        }
+        if bond.as_ref().is_some_and(|bond| bond.amount <= 0) {
+            panic!("Bond amount must be positive");
+        }
+        
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        env.storage().instance().set(&DataKey::VotingPeriod, &config.voting_period);
+        env.storage().instance().set(&DataKey::TieRule, &config.tie_rule);
+        env.storage().instance().set(&DataKey::ExecutionDelay, &config.execution_delay);
+        env.storage().instance().set(&DataKey::Quorum, &config.quorum);
+        env.storage().instance().set(&DataKey::PassThreshold, &config.pass_threshold_bps);
+        match config.weighting {
+            VoteWeighting::OnePerAddress => {}
+            VoteWeighting::Token(governance_token) => {
+                env.storage().instance().set(&DataKey::GovernanceToken, &governance_token);
+            }
+            VoteWeighting::Nft(nft_gate) => {
+                env.storage().instance().set(&DataKey::NftGate, &nft_gate);
+            }
+        }
+        if let Some(notifier) = notifier {
+            env.storage().instance().set(&DataKey::Notifier, &notifier);
+        }
+        if let Some(bond) = bond {
+            env.storage().instance().set(&DataKey::BondConfig, &bond);
+        }
+    }
+
+    pub fn get_notifier(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Notifier)
+    }
+
+    pub fn get_tie_rule(env: Env) -> TieRule {
+        env.storage().instance().get(&DataKey::TieRule).unwrap_or(TieRule::TieFails)
+    }
+
+    pub fn get_voting_period(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::VotingPeriod).unwrap_or(0)
+    }
+
+    pub fn get_execution_delay(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::ExecutionDelay).unwrap_or(0)
+    }
+
+    /// Minimum total vote weight a proposal needs to be executed
+    pub fn get_quorum(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::Quorum).unwrap_or(0)
+    }
+
+    /// Share of the cast votes, in basis points, that must be in favour (zero disables it)
+    pub fn get_pass_threshold(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::PassThreshold).unwrap_or(0)
+    }
+
+    pub fn get_nft_gate(env: Env) -> Option<NftGate> {
+        env.storage().instance().get(&DataKey::NftGate)
+    }
+
+    pub fn get_governance_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::GovernanceToken)
+    }
+
+    /// Votes an address casts: its deposited NFT count in NFT mode, its
+    /// deposited governance tokens in token mode, otherwise one
+    pub fn voting_power(env: Env, voter: Address) -> i128 {
+        if Self::get_nft_gate(env.clone()).is_none() && Self::get_governance_token(env.clone()).is_none() {
+            return 1;
+        }
+        env.storage().persistent().get(&DataKey::Deposit(voter)).unwrap_or(0)
+    }
+
+    pub fn create_proposal(env: Env, creator: Address, title: Symbol, description: Bytes) -> u64 {
+        Self::create_action_proposal(env, creator, title, description, ProposalAction::NoOp)
+    }
+
+    /// Create a proposal whose `action` is applied when it is executed. Voting
+    /// stays open for the configured voting period.
+    pub fn create_action_proposal(
+        env: Env,
+        creator: Address,
+        title: Symbol,
+        description: Bytes,
+        action: ProposalAction,
+    ) -> u64 {
         creator.require_auth();
         
+        match &action {
+            ProposalAction::NoOp => {}
+            ProposalAction::SetVotingPeriod(voting_period) => {
+                if *voting_period == 0 {
+                    panic!("Voting period must be positive");
+                }
+            }
+            ProposalAction::TransferFunds(spend) => {
+                if spend.amount <= 0 {
+                    panic!("Spend amount must be positive");
+                }
+            }
+        }
+        
+        if let Some(gate) = Self::get_nft_gate(env.clone()) {
+            if Self::voting_power(env.clone(), creator.clone()) < gate.min_nfts_to_propose as i128 {
+                panic!("Not enough NFTs to propose");
+            }
+        }
+        
         let proposal_count: u64 = env.storage().instance().get(&DataKey::ProposalCount).unwrap_or(0);
         let proposal_id = proposal_count + 1;
         
@@ -44,8 +298,12 @@ impl VotingContract {
             creator,
             votes_for: 0,
             votes_against: 0,
-            end_time: env.ledger().timestamp() + voting_period,
+            votes_abstain: 0,
+            end_time: env.ledger().timestamp() + Self::get_voting_period(env.clone()),
             executed: false,
+            rejected: false,
+            cancelled: false,
+            action,
         };
         
         env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
@@ -54,11 +312,33 @@ impl VotingContract {
         proposal_id
     }
 
+    pub fn create_spend_proposal(env: Env, creator: Address, title: Symbol, description: Bytes, spend: SpendAction) -> u64 {
+        Self::create_action_proposal(env, creator, title, description, ProposalAction::TransferFunds(spend))
+    }
+
+    pub fn get_spend_action(env: Env, proposal_id: u64) -> Option<SpendAction> {
+        let proposal: Option<Proposal> = env.storage().persistent().get(&DataKey::Proposal(proposal_id));
+        match proposal?.action {
+            ProposalAction::TransferFunds(spend) => Some(spend),
+            _ => None,
+        }
+    }
+
+    /// Spendable balance of `token`, excluding posted bonds and vote deposits held in escrow
+    pub fn treasury_balance(env: Env, token: Address) -> i128 {
+        let held = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        held - Self::escrowed(&env, &token)
+    }
+
     pub fn vote(env: Env, voter: Address, proposal_id: u64, vote: Vote) {
         voter.require_auth();
         
         let mut proposal: Proposal = env.storage().persistent().get(&DataKey::Proposal(proposal_id)).unwrap();
         
+        if proposal.cancelled {
+            panic!("Proposal has been cancelled");
+        }
+        
         // Check if voting period has ended
         if env.ledger().timestamp() > proposal.end_time {
             panic!("Voting period has ended");
@@ -69,13 +349,171 @@ impl VotingContract {
             panic!("User has already voted");
         }
         
-        // Record the vote
-        env.storage().persistent().set(&DataKey::Vote(proposal_id, voter), &vote);
+        // Delegated voting power is cast by the delegate
+        if env.storage().persistent().has(&DataKey::Delegate(voter.clone())) {
+            panic!("Voting power is delegated");
+        }
+        
+        let power = Self::voting_power(env.clone(), voter.clone());
+        if power <= 0 {
+            panic!("No voting power");
+        }
         
-        // Update proposal vote counts
+        // Tally and record the same vote value
         match vote {
-            Vote::For => proposal.votes_for += 1,
-            Vote::Against => proposal.votes_against += 1,
+            Vote::For => proposal.votes_for += power,
+            Vote::Against => proposal.votes_against += power,
+            Vote::Abstain => proposal.votes_abstain += power,
+        }
+        
+        let record = VoteRecord { vote, weight: power };
+        env.storage().persistent().set(&DataKey::Vote(proposal_id, voter.clone()), &record);
+        env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
+        Self::lock_until(&env, &voter, proposal.end_time);
+    }
+
+    /// Deposit governance tokens; they count toward the voter's power until withdrawn
+    pub fn deposit(env: Env, voter: Address, amount: i128) {
+        voter.require_auth();
+        
+        let token = Self::get_governance_token(env.clone()).unwrap_or_else(|| panic!("Token voting is not enabled"));
+        if amount <= 0 {
+            panic!("Deposit amount must be positive");
+        }
+        
+        token::Client::new(&env, &token).transfer(&voter, &env.current_contract_address(), &amount);
+        Self::add_escrow(&env, &token, amount);
+        Self::add_deposit(&env, &voter, amount);
+    }
+
+    /// Return deposited governance tokens once every proposal their power was cast on has closed
+    pub fn withdraw(env: Env, voter: Address, amount: i128) {
+        voter.require_auth();
+        
+        let token = Self::get_governance_token(env.clone()).unwrap_or_else(|| panic!("Token voting is not enabled"));
+        if amount <= 0 || amount > Self::voting_power(env.clone(), voter.clone()) {
+            panic!("Invalid withdrawal amount");
+        }
+        Self::require_unlocked(&env, &voter);
+        
+        Self::add_deposit(&env, &voter, -amount);
+        Self::add_escrow(&env, &token, -amount);
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &voter, &amount);
+    }
+
+    /// Deposit an NFT from the configured collection; it counts toward the
+    /// voter's power until withdrawn
+    pub fn deposit_nft(env: Env, voter: Address, token_id: u64) {
+        voter.require_auth();
+        
+        let gate = Self::get_nft_gate(env.clone()).unwrap_or_else(|| panic!("NFT voting is not enabled"));
+        AccessControlNFTClient::new(&env, &gate.collection).transfer(&voter, &env.current_contract_address(), &token_id);
+        env.storage().persistent().set(&DataKey::DepositedNft(token_id), &voter);
+        Self::add_deposit(&env, &voter, 1);
+    }
+
+    /// Return a deposited NFT once every proposal its power was cast on has closed
+    pub fn withdraw_nft(env: Env, voter: Address, token_id: u64) {
+        voter.require_auth();
+        
+        let gate = Self::get_nft_gate(env.clone()).unwrap_or_else(|| panic!("NFT voting is not enabled"));
+        let depositor: Option<Address> = env.storage().persistent().get(&DataKey::DepositedNft(token_id));
+        if depositor != Some(voter.clone()) {
+            panic!("NFT was not deposited by voter");
+        }
+        Self::require_unlocked(&env, &voter);
+        
+        env.storage().persistent().remove(&DataKey::DepositedNft(token_id));
+        Self::add_deposit(&env, &voter, -1);
+        AccessControlNFTClient::new(&env, &gate.collection).transfer(&env.current_contract_address(), &voter, &token_id);
+    }
+
+    /// Time until which an address's deposits can't be withdrawn, because its
+    /// voting power was cast on a proposal that is still open
+    pub fn locked_until(env: Env, voter: Address) -> u64 {
+        env.storage().persistent().get(&DataKey::LockedUntil(voter)).unwrap_or(0)
+    }
+
+    pub fn delegate(env: Env, delegator: Address, delegate: Address) {
+        delegator.require_auth();
+        
+        if delegator == delegate {
+            panic!("Cannot delegate to self");
+        }
+        if env.storage().persistent().has(&DataKey::Delegate(delegator.clone())) {
+            panic!("Voting power already delegated");
+        }
+        if env.storage().persistent().has(&DataKey::Delegate(delegate.clone())) {
+            panic!("Delegate has delegated their own voting power");
+        }
+        // Power delegated to the delegator can't be passed on a second hop
+        if !Self::get_delegators(env.clone(), delegator.clone()).is_empty() {
+            panic!("Delegator has delegators of their own");
+        }
+        if Self::voting_power(env.clone(), delegator.clone()) <= 0 {
+            panic!("No voting power");
+        }
+        
+        let mut delegators = Self::get_delegators(env.clone(), delegate.clone());
+        delegators.push_back(delegator.clone());
+        env.storage().persistent().set(&DataKey::Delegators(delegate.clone()), &delegators);
+        env.storage().persistent().set(&DataKey::Delegate(delegator), &delegate);
+    }
+
+    pub fn get_delegate(env: Env, delegator: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Delegate(delegator))
+    }
+
+    pub fn get_delegators(env: Env, delegate: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::Delegators(delegate)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn vote_as_delegate(env: Env, delegate: Address, proposal_id: u64, vote: Vote) {
+        delegate.require_auth();
+        
+        let mut proposal: Proposal = env.storage().persistent().get(&DataKey::Proposal(proposal_id)).unwrap();
+        
+        if proposal.cancelled {
+            panic!("Proposal has been cancelled");
+        }
+        
+        // Check if voting period has ended
+        if env.ledger().timestamp() > proposal.end_time {
+            panic!("Voting period has ended");
+        }
+        
+        // Check if delegate has already voted
+        if env.storage().persistent().has(&DataKey::Vote(proposal_id, delegate.clone())) {
+            panic!("User has already voted");
+        }
+        
+        // A delegate that handed its own power on can't cast it again
+        if env.storage().persistent().has(&DataKey::Delegate(delegate.clone())) {
+            panic!("Voting power is delegated");
+        }
+        
+        // Cast for the delegate and every delegator that has not voted yet
+        let mut power = Self::voting_power(env.clone(), delegate.clone());
+        let record = VoteRecord { vote, weight: power };
+        env.storage().persistent().set(&DataKey::Vote(proposal_id, delegate.clone()), &record);
+        Self::lock_until(&env, &delegate, proposal.end_time);
+        for delegator in Self::get_delegators(env.clone(), delegate).iter() {
+            let key = DataKey::Vote(proposal_id, delegator.clone());
+            if !env.storage().persistent().has(&key) {
+                let weight = Self::voting_power(env.clone(), delegator.clone());
+                env.storage().persistent().set(&key, &VoteRecord { vote, weight });
+                Self::lock_until(&env, &delegator, proposal.end_time);
+                power += weight;
+            }
+        }
+        if power <= 0 {
+            panic!("No voting power");
+        }
+        
+        match vote {
+            Vote::For => proposal.votes_for += power,
+            Vote::Against => proposal.votes_against += power,
+            Vote::Abstain => proposal.votes_abstain += power,
         }
         
         env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
@@ -85,27 +523,982 @@ impl VotingContract {
         env.storage().persistent().get(&DataKey::Proposal(proposal_id)).unwrap()
     }
 
-    pub fn execute_proposal(env: Env, proposal_id: u64) {
+    /// Withdraw a proposal before anyone has voted on it (creator only)
+    pub fn cancel_proposal(env: Env, proposal_id: u64) {
         let mut proposal: Proposal = env.storage().persistent().get(&DataKey::Proposal(proposal_id)).unwrap();
+        proposal.creator.require_auth();
+        
+        if proposal.executed {
+            panic!("Proposal has already been executed");
+        }
+        if proposal.cancelled {
+            panic!("Proposal has already been cancelled");
+        }
+        if proposal.rejected {
+            panic!("Proposal has already been rejected");
+        }
+        if proposal.votes_for != 0 || proposal.votes_against != 0 || proposal.votes_abstain != 0 {
+            panic!("Votes have already been cast");
+        }
+        
+        proposal.cancelled = true;
+        env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
+        env.events().publish(
+            (Symbol::new(&env, "proposal_cancelled"),),
+            VotingEvent::ProposalCancelled(proposal_id, proposal.creator),
+        );
+    }
+
+    pub fn proposal_count(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::ProposalCount).unwrap_or(0)
+    }
+
+    /// Proposals with IDs from `start` onward, at most `limit` (capped at
+    /// `MAX_PAGE_SIZE`) IDs scanned, skipping IDs with no stored proposal
+    pub fn list_proposals(env: Env, start: u64, limit: u32) -> Vec<Proposal> {
+        let mut proposals = Vec::new(&env);
+        let count = Self::proposal_count(env.clone());
+        let start = start.max(1);
+        if start > count {
+            return proposals;
+        }
+        
+        let end = count.min(start + limit.min(MAX_PAGE_SIZE) as u64 - 1);
+        for proposal_id in start..=end {
+            let proposal: Option<Proposal> = env.storage().persistent().get(&DataKey::Proposal(proposal_id));
+            if let Some(proposal) = proposal {
+                proposals.push_back(proposal);
+            }
+        }
+        proposals
+    }
+
+    pub fn has_voted(env: Env, proposal_id: u64, voter: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Vote(proposal_id, voter))
+    }
+
+    pub fn get_vote(env: Env, proposal_id: u64, voter: Address) -> Option<Vote> {
+        Self::get_vote_record(env, proposal_id, voter).map(|record| record.vote)
+    }
+
+    pub fn get_vote_record(env: Env, proposal_id: u64, voter: Address) -> Option<VoteRecord> {
+        env.storage().persistent().get(&DataKey::Vote(proposal_id, voter))
+    }
+
+    /// Finalize a proposal whose vote has closed. A proposal that misses quorum
+    /// or fails the vote is marked rejected and reported to the notifier; the
+    /// returned outcome says which.
+    pub fn execute_proposal(env: Env, executor: Address, proposal_id: u64) -> Result<ProposalOutcome, VotingError> {
+        executor.require_auth();
+        
+        // Block reentrant execution from the notifier callback
+        if env.storage().instance().get(&DataKey::Executing).unwrap_or(false) {
+            return Err(VotingError::ReentrantExecution);
+        }
+        
+        let mut proposal: Proposal = env.storage().persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(VotingError::ProposalNotFound)?;
         
         // Check if voting period has ended
         if env.ledger().timestamp() <= proposal.end_time {
-            panic!("Voting period has not ended");
+            return Err(VotingError::VotingNotEnded);
+        }
+        
+        // Check if the timelock after voting has elapsed
+        if env.ledger().timestamp() < proposal.end_time + Self::get_execution_delay(env.clone()) {
+            return Err(VotingError::ExecutionDelayNotElapsed);
         }
         
-        // Check if proposal has already been executed
+        // Check if proposal has already been finalized or was cancelled
         if proposal.executed {
-            panic!("Proposal has already been executed");
+            return Err(VotingError::AlreadyExecuted);
+        }
+        if proposal.rejected {
+            return Err(VotingError::ProposalRejected);
+        }
+        if proposal.cancelled {
+            return Err(VotingError::ProposalCancelled);
         }
         
-        // Check if proposal passed
-        if proposal.votes_for <= proposal.votes_against {
-            panic!("Proposal did not pass");
+        // Check turnout against the quorum; abstentions count toward turnout only
+        let total_votes = proposal.votes_for + proposal.votes_against;
+        if total_votes + proposal.votes_abstain < Self::get_quorum(env.clone()) {
+            return Ok(Self::reject(&env, proposal, ProposalOutcome::QuorumNotMet));
+        }
+        
+        // Check if proposal passed, resolving ties with the configured rule
+        let majority = if proposal.votes_for == proposal.votes_against {
+            Self::get_tie_rule(env.clone()) == TieRule::TiePasses
+        } else {
+            proposal.votes_for > proposal.votes_against
+        };
+        let threshold = Self::get_pass_threshold(env.clone()) as i128;
+        let passed = majority && proposal.votes_for * 10000 >= threshold * total_votes;
+        if !passed {
+            return Ok(Self::reject(&env, proposal, ProposalOutcome::DidNotPass));
         }
         
         proposal.executed = true;
         env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
         
-        // Execute proposal logic here
+        match proposal.action {
+            ProposalAction::NoOp => {}
+            ProposalAction::SetVotingPeriod(voting_period) => {
+                env.storage().instance().set(&DataKey::VotingPeriod, &voting_period);
+            }
+            ProposalAction::TransferFunds(spend) => {
+                Self::post_bond(&env, &executor, proposal_id);
+                
+                if Self::treasury_balance(env.clone(), spend.token.clone()) < spend.amount {
+                    return Err(VotingError::InsufficientTreasury);
+                }
+                
+                token::Client::new(&env, &spend.token).transfer(
+                    &env.current_contract_address(),
+                    &spend.recipient,
+                    &spend.amount,
+                );
+            }
+        }
+        
+        Self::notify_result(&env, proposal_id, true);
+        Ok(ProposalOutcome::Executed)
+    }
+
+    pub fn get_bond(env: Env, proposal_id: u64) -> Option<PostedBond> {
+        env.storage().persistent().get(&DataKey::Bond(proposal_id))
+    }
+
+    pub fn challenge(env: Env, proposal_id: u64) {
+        let config: BondConfig = env.storage().instance().get(&DataKey::BondConfig).unwrap();
+        config.arbiter.require_auth();
+        
+        let bond: PostedBond = env.storage().persistent()
+            .get(&DataKey::Bond(proposal_id))
+            .unwrap_or_else(|| panic!("No bond posted"));
+        if env.ledger().timestamp() > bond.executed_at + config.challenge_window {
+            panic!("Challenge window has closed");
+        }
+        
+        // The slashed bond stays in the contract and becomes spendable treasury
+        env.storage().persistent().remove(&DataKey::Bond(proposal_id));
+        Self::add_escrow(&env, &bond.token, -bond.amount);
+    }
+
+    pub fn release_bond(env: Env, proposal_id: u64) {
+        let config: BondConfig = env.storage().instance().get(&DataKey::BondConfig).unwrap();
+        let bond: PostedBond = env.storage().persistent()
+            .get(&DataKey::Bond(proposal_id))
+            .unwrap_or_else(|| panic!("No bond posted"));
+        if env.ledger().timestamp() <= bond.executed_at + config.challenge_window {
+            panic!("Challenge window still open");
+        }
+        
+        env.storage().persistent().remove(&DataKey::Bond(proposal_id));
+        Self::add_escrow(&env, &bond.token, -bond.amount);
+        token::Client::new(&env, &bond.token).transfer(
+            &env.current_contract_address(),
+            &bond.executor,
+            &bond.amount,
+        );
+    }
+
+    fn post_bond(env: &Env, executor: &Address, proposal_id: u64) {
+        let config: Option<BondConfig> = env.storage().instance().get(&DataKey::BondConfig);
+        if let Some(config) = config {
+            token::Client::new(env, &config.token).transfer(
+                executor,
+                &env.current_contract_address(),
+                &config.amount,
+            );
+            Self::add_escrow(env, &config.token, config.amount);
+            
+            let bond = PostedBond {
+                executor: executor.clone(),
+                token: config.token,
+                amount: config.amount,
+                executed_at: env.ledger().timestamp(),
+            };
+            env.storage().persistent().set(&DataKey::Bond(proposal_id), &bond);
+        }
+    }
+
+    fn add_deposit(env: &Env, voter: &Address, amount: i128) {
+        let deposit = Self::voting_power(env.clone(), voter.clone()) + amount;
+        env.storage().persistent().set(&DataKey::Deposit(voter.clone()), &deposit);
+    }
+
+    /// Keep a voter's deposits in place until `end_time`, so the same power
+    /// can't be moved to another address and cast again
+    fn lock_until(env: &Env, voter: &Address, end_time: u64) {
+        if end_time > Self::locked_until(env.clone(), voter.clone()) {
+            env.storage().persistent().set(&DataKey::LockedUntil(voter.clone()), &end_time);
+        }
+    }
+
+    fn require_unlocked(env: &Env, voter: &Address) {
+        if env.ledger().timestamp() <= Self::locked_until(env.clone(), voter.clone()) {
+            panic!("Voting power is locked");
+        }
+    }
+
+    fn escrowed(env: &Env, token: &Address) -> i128 {
+        env.storage().instance().get(&DataKey::Escrowed(token.clone())).unwrap_or(0)
+    }
+
+    fn add_escrow(env: &Env, token: &Address, amount: i128) {
+        let escrowed = Self::escrowed(env, token) + amount;
+        env.storage().instance().set(&DataKey::Escrowed(token.clone()), &escrowed);
+    }
+
+    fn reject(env: &Env, mut proposal: Proposal, outcome: ProposalOutcome) -> ProposalOutcome {
+        proposal.rejected = true;
+        env.storage().persistent().set(&DataKey::Proposal(proposal.id), &proposal);
+        Self::notify_result(env, proposal.id, false);
+        outcome
+    }
+
+    /// Tell the notifier whether a finalized proposal passed
+    fn notify_result(env: &Env, proposal_id: u64, passed: bool) {
+        let notifier: Option<Address> = env.storage().instance().get(&DataKey::Notifier);
+        if let Some(notifier) = notifier {
+            env.storage().instance().set(&DataKey::Executing, &true);
+            env.invoke_contract::<()>(
+                &notifier,
+                &Symbol::new(env, "on_proposal_result"),
+                vec![env, proposal_id.into_val(env), passed.into_val(env)],
+            );
+            env.storage().instance().set(&DataKey::Executing, &false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_control_nft::AccessControlNFT;
+    use soroban_sdk::{contract, contractimpl, symbol_short, testutils::{Address as _, Ledger}, token, Address, Bytes, Env};
+
+    fn default_config() -> VotingConfig {
+        VotingConfig {
+            voting_period: 100,
+            tie_rule: TieRule::TieFails,
+            execution_delay: 0,
+            quorum: 0,
+            pass_threshold_bps: 0,
+            weighting: VoteWeighting::OnePerAddress,
+        }
+    }
+
+    #[contract]
+    pub struct MockNotifier;
+
+    #[contractimpl]
+    impl MockNotifier {
+        pub fn on_proposal_result(env: Env, proposal_id: u64, passed: bool) {
+            env.storage().instance().set(&symbol_short!("result"), &(proposal_id, passed));
+        }
+
+        pub fn last_result(env: Env) -> Option<(u64, bool)> {
+            env.storage().instance().get(&symbol_short!("result"))
+        }
+    }
+
+    #[test]
+    fn test_execute_notifies_result() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let notifier_id = env.register_contract(None, MockNotifier);
+        let notifier = MockNotifierClient::new(&env, &notifier_id);
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(&env, &contract_id);
+        
+        client.initialize(&default_config(), &Some(notifier_id.clone()), &None);
+        assert_eq!(client.get_notifier(), Some(notifier_id));
+        
+        let creator = Address::generate(&env);
+        let proposal_id = client.create_proposal(
+            &creator,
+            &Symbol::new(&env, "upgrade"),
+            &Bytes::from_array(&env, &[1, 2, 3]),
+        );
+        let failing_id = client.create_proposal(
+            &creator,
+            &Symbol::new(&env, "rollback"),
+            &Bytes::from_array(&env, &[4]),
+        );
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+        client.vote(&Address::generate(&env), &failing_id, &Vote::Against);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        assert_eq!(client.execute_proposal(&Address::generate(&env), &proposal_id), ProposalOutcome::Executed);
+        assert!(client.get_proposal(&proposal_id).executed);
+        assert_eq!(notifier.last_result(), Some((proposal_id, true)));
+        
+        assert_eq!(client.execute_proposal(&Address::generate(&env), &failing_id), ProposalOutcome::DidNotPass);
+        assert!(client.get_proposal(&failing_id).rejected);
+        assert_eq!(notifier.last_result(), Some((failing_id, false)));
+        
+        let result = client.try_execute_proposal(&Address::generate(&env), &failing_id);
+        assert_eq!(result, Err(Ok(VotingError::ProposalRejected)));
+    }
+
+    #[test]
+    fn test_treasury_spend_across_tokens() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(&env, &contract_id);
+        client.initialize(&default_config(), &None, &None);
+        
+        let token_a = env.register_stellar_asset_contract(Address::generate(&env));
+        let token_b = env.register_stellar_asset_contract(Address::generate(&env));
+        token::StellarAssetClient::new(&env, &token_a).mint(&contract_id, &1000);
+        token::StellarAssetClient::new(&env, &token_b).mint(&contract_id, &500);
+        
+        let creator = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        let description = Bytes::from_array(&env, &[1]);
+        let spend_a = client.create_spend_proposal(
+            &creator,
+            &Symbol::new(&env, "grant_a"),
+            &description,
+            &SpendAction { token: token_a.clone(), recipient: recipient.clone(), amount: 400 },
+        );
+        let spend_b = client.create_spend_proposal(
+            &creator,
+            &Symbol::new(&env, "grant_b"),
+            &description,
+            &SpendAction { token: token_b.clone(), recipient: recipient.clone(), amount: 500 },
+        );
+        
+        let voter = Address::generate(&env);
+        client.vote(&voter, &spend_a, &Vote::For);
+        client.vote(&voter, &spend_b, &Vote::For);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        client.execute_proposal(&Address::generate(&env), &spend_a);
+        client.execute_proposal(&Address::generate(&env), &spend_b);
+        
+        assert_eq!(client.treasury_balance(&token_a), 600);
+        assert_eq!(client.treasury_balance(&token_b), 0);
+        assert_eq!(token::Client::new(&env, &token_a).balance(&recipient), 400);
+        assert_eq!(token::Client::new(&env, &token_b).balance(&recipient), 500);
+    }
+
+    fn deadlocked_proposal(env: &Env, tie_rule: TieRule) -> (VotingContractClient<'_>, u64) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(env, &contract_id);
+        client.initialize(&VotingConfig { tie_rule, ..default_config() }, &None, &None);
+        
+        let proposal_id = client.create_proposal(
+            &Address::generate(env),
+            &Symbol::new(env, "deadlock"),
+            &Bytes::from_array(env, &[1]),
+        );
+        client.vote(&Address::generate(env), &proposal_id, &Vote::For);
+        client.vote(&Address::generate(env), &proposal_id, &Vote::Against);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        (client, proposal_id)
+    }
+
+    #[test]
+    fn test_tie_fails() {
+        let env = Env::default();
+        let (client, proposal_id) = deadlocked_proposal(&env, TieRule::TieFails);
+        
+        let outcome = client.execute_proposal(&Address::generate(&env), &proposal_id);
+        assert_eq!(outcome, ProposalOutcome::DidNotPass);
+        assert!(!client.get_proposal(&proposal_id).executed);
+    }
+
+    #[test]
+    fn test_tie_passes() {
+        let env = Env::default();
+        let (client, proposal_id) = deadlocked_proposal(&env, TieRule::TiePasses);
+        
+        assert_eq!(client.get_tie_rule(), TieRule::TiePasses);
+        client.execute_proposal(&Address::generate(&env), &proposal_id);
+        assert!(client.get_proposal(&proposal_id).executed);
+    }
+
+    fn passed_proposal_with_delay(env: &Env, execution_delay: u64) -> (VotingContractClient<'_>, u64) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(env, &contract_id);
+        client.initialize(&VotingConfig { execution_delay, ..default_config() }, &None, &None);
+        
+        let proposal_id = client.create_proposal(
+            &Address::generate(env),
+            &Symbol::new(env, "timelock"),
+            &Bytes::from_array(env, &[1]),
+        );
+        client.vote(&Address::generate(env), &proposal_id, &Vote::For);
+        (client, proposal_id)
+    }
+
+    #[test]
+    fn test_execution_rejected_during_delay() {
+        let env = Env::default();
+        let (client, proposal_id) = passed_proposal_with_delay(&env, 50);
+        
+        env.ledger().with_mut(|li| li.timestamp = 149);
+        let result = client.try_execute_proposal(&Address::generate(&env), &proposal_id);
+        assert_eq!(result, Err(Ok(VotingError::ExecutionDelayNotElapsed)));
+    }
+
+    #[test]
+    fn test_execution_allowed_after_delay() {
+        let env = Env::default();
+        let (client, proposal_id) = passed_proposal_with_delay(&env, 50);
+        
+        env.ledger().with_mut(|li| li.timestamp = 150);
+        client.execute_proposal(&Address::generate(&env), &proposal_id);
+        assert!(client.get_proposal(&proposal_id).executed);
+    }
+
+    fn executed_bonded_spend(env: &Env) -> (VotingContractClient<'_>, Address, Address, u64) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(env, &contract_id);
+        
+        let treasury_token = env.register_stellar_asset_contract(Address::generate(env));
+        let bond_token = env.register_stellar_asset_contract(Address::generate(env));
+        let executor = Address::generate(env);
+        token::StellarAssetClient::new(env, &treasury_token).mint(&contract_id, &1000);
+        token::StellarAssetClient::new(env, &bond_token).mint(&executor, &50);
+        
+        let bond = BondConfig {
+            token: bond_token.clone(),
+            amount: 50,
+            challenge_window: 1000,
+            arbiter: Address::generate(env),
+        };
+        client.initialize(&default_config(), &None, &Some(bond));
+        
+        let proposal_id = client.create_spend_proposal(
+            &Address::generate(env),
+            &Symbol::new(env, "grant"),
+            &Bytes::from_array(env, &[1]),
+            &SpendAction { token: treasury_token, recipient: Address::generate(env), amount: 400 },
+        );
+        client.vote(&Address::generate(env), &proposal_id, &Vote::For);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        client.execute_proposal(&executor, &proposal_id);
+        assert_eq!(token::Client::new(env, &bond_token).balance(&executor), 0);
+        
+        (client, executor, bond_token, proposal_id)
+    }
+
+    #[test]
+    fn test_clean_execution_returns_bond() {
+        let env = Env::default();
+        let (client, executor, bond_token, proposal_id) = executed_bonded_spend(&env);
+        
+        env.ledger().with_mut(|li| li.timestamp = 1102);
+        client.release_bond(&proposal_id);
+        
+        assert_eq!(token::Client::new(&env, &bond_token).balance(&executor), 50);
+        assert!(client.get_bond(&proposal_id).is_none());
+    }
+
+    #[test]
+    fn test_challenge_slashes_bond() {
+        let env = Env::default();
+        let (client, executor, bond_token, proposal_id) = executed_bonded_spend(&env);
+        
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        client.challenge(&proposal_id);
+        
+        assert!(client.get_bond(&proposal_id).is_none());
+        assert_eq!(token::Client::new(&env, &bond_token).balance(&executor), 0);
+        assert_eq!(client.treasury_balance(&bond_token), 50);
+    }
+
+    #[test]
+    fn test_bonds_are_not_spendable_treasury() {
+        let env = Env::default();
+        let (client, executor, bond_token, bonded_id) = executed_bonded_spend(&env);
+        assert_eq!(client.treasury_balance(&bond_token), 0);
+        
+        token::StellarAssetClient::new(&env, &bond_token).mint(&executor, &50);
+        let raid_id = client.create_spend_proposal(
+            &Address::generate(&env),
+            &Symbol::new(&env, "raid"),
+            &Bytes::from_array(&env, &[1]),
+            &SpendAction { token: bond_token.clone(), recipient: Address::generate(&env), amount: 50 },
+        );
+        client.vote(&Address::generate(&env), &raid_id, &Vote::For);
+        
+        env.ledger().with_mut(|li| li.timestamp = 202);
+        let result = client.try_execute_proposal(&executor, &raid_id);
+        assert_eq!(result, Err(Ok(VotingError::InsufficientTreasury)));
+        
+        env.ledger().with_mut(|li| li.timestamp = 1102);
+        client.release_bond(&bonded_id);
+        assert_eq!(token::Client::new(&env, &bond_token).balance(&executor), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bond amount must be positive")]
+    fn test_bond_amount_must_be_positive() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(&env, &contract_id);
+        let bond = BondConfig {
+            token: Address::generate(&env),
+            amount: 0,
+            challenge_window: 1000,
+            arbiter: Address::generate(&env),
+        };
+        
+        client.initialize(&default_config(), &None, &Some(bond));
+    }
+
+    fn open_proposal(env: &Env) -> (VotingContractClient<'_>, u64) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(env, &contract_id);
+        client.initialize(&default_config(), &None, &None);
+        
+        let proposal_id = client.create_proposal(
+            &Address::generate(env),
+            &Symbol::new(env, "delegated"),
+            &Bytes::from_array(env, &[1]),
+        );
+        (client, proposal_id)
+    }
+
+    #[test]
+    fn test_vote_as_delegate_combines_power() {
+        let env = Env::default();
+        let (client, proposal_id) = open_proposal(&env);
+        let delegate = Address::generate(&env);
+        
+        client.delegate(&Address::generate(&env), &delegate);
+        client.delegate(&Address::generate(&env), &delegate);
+        assert_eq!(client.get_delegators(&delegate).len(), 2);
+        
+        client.vote_as_delegate(&delegate, &proposal_id, &Vote::For);
+        assert_eq!(client.get_proposal(&proposal_id).votes_for, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Voting power is delegated")]
+    fn test_delegator_cannot_vote_separately() {
+        let env = Env::default();
+        let (client, proposal_id) = open_proposal(&env);
+        let delegate = Address::generate(&env);
+        let delegator = Address::generate(&env);
+        
+        client.delegate(&delegator, &delegate);
+        client.vote(&delegator, &proposal_id, &Vote::Against);
+    }
+
+    #[test]
+    #[should_panic(expected = "Voting power is delegated")]
+    fn test_delegator_cannot_vote_as_delegate() {
+        let env = Env::default();
+        let (client, proposal_id) = open_proposal(&env);
+        let delegator = Address::generate(&env);
+        
+        client.delegate(&delegator, &Address::generate(&env));
+        client.vote_as_delegate(&delegator, &proposal_id, &Vote::Against);
+    }
+
+    #[test]
+    #[should_panic(expected = "Delegator has delegators of their own")]
+    fn test_delegate_cannot_delegate_onward() {
+        let env = Env::default();
+        let (client, _proposal_id) = open_proposal(&env);
+        let delegate = Address::generate(&env);
+        
+        client.delegate(&Address::generate(&env), &delegate);
+        client.delegate(&delegate, &Address::generate(&env));
+    }
+
+    #[test]
+    #[should_panic(expected = "No voting power")]
+    fn test_delegation_requires_voting_power() {
+        let env = Env::default();
+        let (client, _nft, _minter, _proposal_id) = nft_gated_proposal(&env);
+        
+        client.delegate(&Address::generate(&env), &Address::generate(&env));
+    }
+
+    #[test]
+    #[should_panic(expected = "No voting power")]
+    fn test_vote_as_delegate_requires_voting_power() {
+        let env = Env::default();
+        let (client, _nft, _minter, proposal_id) = nft_gated_proposal(&env);
+        
+        client.vote_as_delegate(&Address::generate(&env), &proposal_id, &Vote::For);
+    }
+
+    fn deposit_nfts(client: &VotingContractClient, nft: &AccessControlNFTClient, minter: &Address, voter: &Address, count: u32) {
+        for _ in 0..count {
+            let token_id = nft.mint(minter, voter, &Bytes::from_array(&client.env, &[1]));
+            client.deposit_nft(voter, &token_id);
+        }
+    }
+
+    fn nft_gated_proposal(env: &Env) -> (VotingContractClient<'_>, AccessControlNFTClient<'_>, Address, u64) {
+        env.mock_all_auths();
+        let nft_id = env.register_contract(None, AccessControlNFT);
+        let nft = AccessControlNFTClient::new(env, &nft_id);
+        let admin = Address::generate(env);
+        nft.initialize(
+            &admin,
+            &Symbol::new(env, "GovNFT"),
+            &Symbol::new(env, "GOV"),
+            &Vec::from_array(env, [admin.clone()]),
+            &1,
+        );
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(env, &contract_id);
+        let gate = NftGate { collection: nft_id, min_nfts_to_propose: 2 };
+        client.initialize(&VotingConfig { weighting: VoteWeighting::Nft(gate), ..default_config() }, &None, &None);
+        
+        let creator = Address::generate(env);
+        deposit_nfts(&client, &nft, &admin, &creator, 2);
+        let proposal_id = client.create_proposal(
+            &creator,
+            &Symbol::new(env, "nft_gov"),
+            &Bytes::from_array(env, &[1]),
+        );
+        (client, nft, admin, proposal_id)
+    }
+
+    #[test]
+    fn test_nft_count_weights_votes() {
+        let env = Env::default();
+        let (client, nft, minter, proposal_id) = nft_gated_proposal(&env);
+        let whale = Address::generate(&env);
+        let holder = Address::generate(&env);
+        deposit_nfts(&client, &nft, &minter, &whale, 3);
+        deposit_nfts(&client, &nft, &minter, &holder, 1);
+        
+        assert_eq!(client.voting_power(&whale), 3);
+        client.vote(&whale, &proposal_id, &Vote::For);
+        client.vote(&holder, &proposal_id, &Vote::Against);
+        
+        let proposal = client.get_proposal(&proposal_id);
+        assert_eq!(proposal.votes_for, 3);
+        assert_eq!(proposal.votes_against, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "No voting power")]
+    fn test_nft_mode_rejects_non_holder() {
+        let env = Env::default();
+        let (client, _nft, _minter, proposal_id) = nft_gated_proposal(&env);
+        
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough NFTs to propose")]
+    fn test_nft_mode_gates_proposals() {
+        let env = Env::default();
+        let (client, nft, minter, _proposal_id) = nft_gated_proposal(&env);
+        let creator = Address::generate(&env);
+        deposit_nfts(&client, &nft, &minter, &creator, 1);
+        
+        client.create_proposal(&creator, &Symbol::new(&env, "small"), &Bytes::from_array(&env, &[1]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Voting power is locked")]
+    fn test_voted_nft_locked_until_close() {
+        let env = Env::default();
+        let (client, nft, minter, proposal_id) = nft_gated_proposal(&env);
+        let holder = Address::generate(&env);
+        let token_id = nft.mint(&minter, &holder, &Bytes::from_array(&env, &[1]));
+        client.deposit_nft(&holder, &token_id);
+        client.vote(&holder, &proposal_id, &Vote::For);
+        
+        // Withdrawing to vote again from another address is refused
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.withdraw_nft(&holder, &token_id);
+    }
+
+    #[test]
+    fn test_nft_withdrawn_after_close() {
+        let env = Env::default();
+        let (client, nft, minter, proposal_id) = nft_gated_proposal(&env);
+        let holder = Address::generate(&env);
+        let token_id = nft.mint(&minter, &holder, &Bytes::from_array(&env, &[1]));
+        client.deposit_nft(&holder, &token_id);
+        client.vote(&holder, &proposal_id, &Vote::For);
+        assert_eq!(client.locked_until(&holder), 100);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        client.withdraw_nft(&holder, &token_id);
+        assert_eq!(nft.owner_of(&token_id), holder);
+        assert_eq!(client.voting_power(&holder), 0);
+        assert_eq!(client.get_proposal(&proposal_id).votes_for, 1);
+    }
+
+    #[test]
+    fn test_vote_queries() {
+        let env = Env::default();
+        let (client, proposal_id) = open_proposal(&env);
+        let voter = Address::generate(&env);
+        
+        assert!(!client.has_voted(&proposal_id, &voter));
+        assert_eq!(client.get_vote(&proposal_id, &voter), None);
+        
+        client.vote(&voter, &proposal_id, &Vote::Against);
+        assert!(client.has_voted(&proposal_id, &voter));
+        assert_eq!(client.get_vote(&proposal_id, &voter), Some(Vote::Against));
+        assert_eq!(client.get_proposal(&proposal_id).votes_against, 1);
+    }
+
+    #[test]
+    fn test_token_weighted_votes_snapshot_balance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let governance_token = env.register_stellar_asset_contract(Address::generate(&env));
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(&env, &contract_id);
+        let weighting = VoteWeighting::Token(governance_token.clone());
+        client.initialize(&VotingConfig { weighting, ..default_config() }, &None, &None);
+        
+        let whale = Address::generate(&env);
+        let minnow = Address::generate(&env);
+        let token_admin = token::StellarAssetClient::new(&env, &governance_token);
+        token_admin.mint(&whale, &500);
+        token_admin.mint(&minnow, &20);
+        client.deposit(&whale, &500);
+        client.deposit(&minnow, &20);
+        
+        let proposal_id = client.create_proposal(
+            &Address::generate(&env),
+            &Symbol::new(&env, "weighted"),
+            &Bytes::from_array(&env, &[1]),
+        );
+        client.vote(&whale, &proposal_id, &Vote::For);
+        client.vote(&minnow, &proposal_id, &Vote::Against);
+        
+        // Later deposits don't alter the recorded weight
+        token_admin.mint(&whale, &1000);
+        client.deposit(&whale, &1000);
+        let proposal = client.get_proposal(&proposal_id);
+        assert_eq!(proposal.votes_for, 500);
+        assert_eq!(proposal.votes_against, 20);
+        assert_eq!(
+            client.get_vote_record(&proposal_id, &whale),
+            Some(VoteRecord { vote: Vote::For, weight: 500 })
+        );
+    }
+
+    #[test]
+    fn test_voted_tokens_locked_until_close() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let governance_token = env.register_stellar_asset_contract(Address::generate(&env));
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(&env, &contract_id);
+        let weighting = VoteWeighting::Token(governance_token.clone());
+        client.initialize(&VotingConfig { weighting, ..default_config() }, &None, &None);
+        
+        let voter = Address::generate(&env);
+        token::StellarAssetClient::new(&env, &governance_token).mint(&voter, &300);
+        client.deposit(&voter, &300);
+        let proposal_id = client.create_proposal(
+            &Address::generate(&env),
+            &Symbol::new(&env, "locked"),
+            &Bytes::from_array(&env, &[1]),
+        );
+        client.vote(&voter, &proposal_id, &Vote::For);
+        
+        // Deposits are neither withdrawable before the vote closes nor treasury funds
+        assert!(client.try_withdraw(&voter, &300).is_err());
+        assert_eq!(client.treasury_balance(&governance_token), 0);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        client.withdraw(&voter, &300);
+        assert_eq!(token::Client::new(&env, &governance_token).balance(&voter), 300);
+        assert_eq!(client.voting_power(&voter), 0);
+    }
+
+    fn proposal_with_rules(env: &Env, quorum: i128, pass_threshold_bps: u32) -> (VotingContractClient<'_>, u64) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(env, &contract_id);
+        client.initialize(&VotingConfig { quorum, pass_threshold_bps, ..default_config() }, &None, &None);
+        
+        let proposal_id = client.create_proposal(
+            &Address::generate(env),
+            &Symbol::new(env, "turnout"),
+            &Bytes::from_array(env, &[1]),
+        );
+        (client, proposal_id)
+    }
+
+    #[test]
+    fn test_majority_without_quorum_rejected() {
+        let env = Env::default();
+        let (client, proposal_id) = proposal_with_rules(&env, 3, 0);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        let outcome = client.execute_proposal(&Address::generate(&env), &proposal_id);
+        assert_eq!(outcome, ProposalOutcome::QuorumNotMet);
+        assert!(!client.get_proposal(&proposal_id).executed);
+    }
+
+    #[test]
+    fn test_pass_threshold_requires_supermajority() {
+        let env = Env::default();
+        let (client, proposal_id) = proposal_with_rules(&env, 0, 6667);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::Against);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        let outcome = client.execute_proposal(&Address::generate(&env), &proposal_id);
+        assert_eq!(outcome, ProposalOutcome::DidNotPass);
+    }
+
+    #[test]
+    fn test_abstain_counts_toward_quorum_only() {
+        let env = Env::default();
+        let (client, proposal_id) = proposal_with_rules(&env, 3, 0);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::Against);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::Abstain);
+        
+        let proposal = client.get_proposal(&proposal_id);
+        assert_eq!(proposal.votes_abstain, 1);
+        
+        // Quorum is met, but the 1-1 tie still fails under TieFails
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        let outcome = client.execute_proposal(&Address::generate(&env), &proposal_id);
+        assert_eq!(outcome, ProposalOutcome::DidNotPass);
+    }
+
+    #[test]
+    fn test_list_proposals_skips_missing_ids() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(&env, &contract_id);
+        client.initialize(&default_config(), &None, &None);
+        
+        for _ in 0..5 {
+            client.create_proposal(&Address::generate(&env), &Symbol::new(&env, "page"), &Bytes::from_array(&env, &[1]));
+        }
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().remove(&DataKey::Proposal(2));
+            env.storage().persistent().remove(&DataKey::Proposal(4));
+        });
+        assert_eq!(client.proposal_count(), 5);
+        
+        let page = client.list_proposals(&1, &3);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page.get(0).unwrap().id, 1);
+        assert_eq!(page.get(1).unwrap().id, 3);
+        
+        let page = client.list_proposals(&3, &50);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page.get(1).unwrap().id, 5);
+        
+        assert_eq!(client.list_proposals(&6, &10).len(), 0);
+        assert_eq!(client.list_proposals(&1, &0).len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Votes have already been cast")]
+    fn test_cancel_after_votes_rejected() {
+        let env = Env::default();
+        let (client, proposal_id) = open_proposal(&env);
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::Abstain);
+        
+        client.cancel_proposal(&proposal_id);
+    }
+
+    #[test]
+    fn test_cancelled_proposal_cannot_execute() {
+        let env = Env::default();
+        let (client, proposal_id) = open_proposal(&env);
+        
+        client.cancel_proposal(&proposal_id);
+        assert!(client.get_proposal(&proposal_id).cancelled);
+        assert!(client.try_vote(&Address::generate(&env), &proposal_id, &Vote::For).is_err());
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        let result = client.try_execute_proposal(&Address::generate(&env), &proposal_id);
+        assert_eq!(result, Err(Ok(VotingError::ProposalCancelled)));
+    }
+
+    #[test]
+    fn test_set_voting_period_action() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let contract_id = env.register_contract(None, VotingContract);
+        let client = VotingContractClient::new(&env, &contract_id);
+        client.initialize(&default_config(), &None, &None);
+        assert_eq!(client.get_voting_period(), 100);
+        
+        let proposal_id = client.create_action_proposal(
+            &Address::generate(&env),
+            &Symbol::new(&env, "period"),
+            &Bytes::from_array(&env, &[1]),
+            &ProposalAction::SetVotingPeriod(604800),
+        );
+        client.vote(&Address::generate(&env), &proposal_id, &Vote::For);
+        
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        client.execute_proposal(&Address::generate(&env), &proposal_id);
+        
+        assert_eq!(client.get_voting_period(), 604800);
+        let stored: Option<u64> = env.as_contract(&contract_id, || {
+            env.storage().instance().get(&DataKey::VotingPeriod)
+        });
+        assert_eq!(stored, Some(604800));
+        
+        // New proposals stay open for the updated period
+        let next_id = client.create_proposal(&Address::generate(&env), &Symbol::new(&env, "next"), &Bytes::from_array(&env, &[1]));
+        assert_eq!(client.get_proposal(&next_id).end_time, 101 + 604800);
+    }
+
+    #[test]
+    fn test_spend_action_read_from_proposal() {
+        let env = Env::default();
+        let (client, proposal_id) = open_proposal(&env);
+        assert_eq!(client.get_spend_action(&proposal_id), None);
+        
+        let spend = SpendAction { token: Address::generate(&env), recipient: Address::generate(&env), amount: 10 };
+        let spend_id = client.create_action_proposal(
+            &Address::generate(&env),
+            &Symbol::new(&env, "spend"),
+            &Bytes::from_array(&env, &[1]),
+            &ProposalAction::TransferFunds(spend.clone()),
+        );
+        assert_eq!(client.get_spend_action(&spend_id), Some(spend));
+    }
+
+    #[test]
+    #[should_panic(expected = "Spend amount must be positive")]
+    fn test_action_proposal_rejects_empty_spend() {
+        let env = Env::default();
+        let (client, _proposal_id) = open_proposal(&env);
+        let spend = SpendAction { token: Address::generate(&env), recipient: Address::generate(&env), amount: 0 };
+        
+        client.create_action_proposal(
+            &Address::generate(&env),
+            &Symbol::new(&env, "spend"),
+            &Bytes::from_array(&env, &[1]),
+            &ProposalAction::TransferFunds(spend),
+        );
     }
 }